@@ -0,0 +1,68 @@
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use rshtml::EscapingWriter;
+use std::fmt::{self, Write};
+
+// The previous char by char writer, to compare against.
+struct CharEscapingWriter<'a> {
+    inner: &'a mut dyn Write,
+}
+
+impl Write for CharEscapingWriter<'_> {
+    fn write_str(&mut self, input: &str) -> fmt::Result {
+        for c in input.chars() {
+            match c {
+                '&' => self.inner.write_str("&amp;")?,
+                '<' => self.inner.write_str("&lt;")?,
+                '>' => self.inner.write_str("&gt;")?,
+                '"' => self.inner.write_str("&quot;")?,
+                '\'' => self.inner.write_str("&#39;")?,
+                '/' => self.inner.write_str("&#x2F;")?,
+                _ => self.inner.write_char(c)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn inputs() -> [(&'static str, String); 4] {
+    let sentence = "The quick brown fox jumps over the lazy dog. ";
+    [
+        ("plain", sentence.repeat(100)),
+        ("unicode", "Ünïcödé tëxt – 中文 – emoji 😀. ".repeat(100)),
+        (
+            "mixed",
+            format!("{sentence}<a href=\"/x\">link</a> & 'quote' ").repeat(60),
+        ),
+        ("dense", "<>&\"'/".repeat(400)),
+    ]
+}
+
+fn escaping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("escaping");
+
+    for (name, input) in inputs() {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        let mut out = String::with_capacity(input.len() * 6);
+
+        group.bench_with_input(BenchmarkId::new("bulk", name), &input, |b, input| {
+            b.iter(|| {
+                out.clear();
+                let out: &mut dyn Write = &mut out;
+                EscapingWriter { inner: out }.write_str(black_box(input))
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("char", name), &input, |b, input| {
+            b.iter(|| {
+                out.clear();
+                CharEscapingWriter { inner: &mut out }.write_str(black_box(input))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, escaping);
+criterion_main!(benches);
@@ -0,0 +1,245 @@
+use crate::traits::View;
+use std::{
+    cell::RefCell,
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+/// A view that awaits its data while rendering, e.g. a sidebar widget loading from a database.
+///
+/// Any future resolving to a [`View`] is an `AsyncView`. Wrapped in [`Async`], it can be
+/// used as a leaf anywhere in a view tree, which is then rendered with [`render_async`].
+pub trait AsyncView {
+    fn render_async(
+        self,
+        out: &mut (dyn fmt::Write + Send),
+    ) -> impl Future<Output = fmt::Result> + Send;
+}
+
+impl<F, V> AsyncView for F
+where
+    F: Future<Output = V> + Send,
+    V: View,
+{
+    async fn render_async(self, out: &mut (dyn fmt::Write + Send)) -> fmt::Result {
+        self.await.render(out)
+    }
+}
+
+type SlotFuture = Pin<Box<dyn Future<Output = Result<String, fmt::Error>> + Send>>;
+
+// The render currently being polled by each `Resolve`, innermost last.
+thread_local! {
+    static FRAMES: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+// The slots of a render are kept here by byte offset rather than marked in its output,
+// which may contain any text. Writers wrapping the one of the render pass the output on,
+// so the offset of a leaf is the output counted so far.
+#[derive(Default)]
+struct Frame {
+    written: usize,
+    slots: Vec<(usize, SlotFuture)>,
+    buffered: usize, // renders into a buffer that is written out later, see `buffered`
+}
+
+// The writer of a render, counting its output in the current frame.
+struct SlotWriter<'a>(&'a mut String);
+
+impl fmt::Write for SlotWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.push_str(s);
+        FRAMES.with(|frames| {
+            if let Some(frame) = frames.borrow_mut().last_mut() {
+                frame.written += s.len();
+            }
+        });
+        Ok(())
+    }
+}
+
+// Marks a render into a buffer that is written out later, e.g. by `Memo`, so an `Async`
+// leaf inside it fails instead of filling its slot at the wrong offset.
+pub(crate) fn buffered<R>(render: impl FnOnce() -> R) -> R {
+    struct Guard(bool);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            if self.0 {
+                FRAMES.with(|frames| {
+                    if let Some(frame) = frames.borrow_mut().last_mut() {
+                        frame.buffered -= 1;
+                    }
+                });
+            }
+        }
+    }
+
+    let _guard = Guard(FRAMES.with(|frames| {
+        frames
+            .borrow_mut()
+            .last_mut()
+            .map(|frame| frame.buffered += 1)
+            .is_some()
+    }));
+
+    render()
+}
+
+// The async leaves of the current render so far, e.g. to tell if a view rendered anything.
+pub(crate) fn slot_count() -> usize {
+    FRAMES.with(|frames| frames.borrow().last().map_or(0, |frame| frame.slots.len()))
+}
+
+/// An async leaf in a sync view tree: `{Async::new(async move { v!(<p>{load().await}</p>) })}`.
+///
+/// It renders only once and only inside [`render_async`]. Writers that wrap the output, e.g. of
+/// an escaper or a component, are fine, but a sync render, or one into a buffer that is written
+/// out later such as a [`Memo`](crate::Memo), a cache or `render_to_string`, fails with `fmt::Error`.
+pub struct Async<T>(Mutex<Option<T>>);
+
+impl<T> Async<T> {
+    pub fn new(view: T) -> Self {
+        Self(Mutex::new(Some(view)))
+    }
+}
+
+impl<T: AsyncView + Send + 'static> View for Async<T> {
+    // The output is written into the slot of the leaf once it is resolved.
+    fn render(&self, _out: &mut dyn fmt::Write) -> fmt::Result {
+        let view = self
+            .0
+            .lock()
+            .map_err(|_| fmt::Error)?
+            .take()
+            .ok_or(fmt::Error)?;
+
+        FRAMES.with(|frames| {
+            let mut frames = frames.borrow_mut();
+            let frame = frames
+                .last_mut()
+                .filter(|frame| frame.buffered == 0)
+                .ok_or(fmt::Error)?;
+
+            frame.slots.push((
+                frame.written,
+                Box::pin(Resolve::new(async move {
+                    let mut out = String::new();
+                    view.render_async(&mut SlotWriter(&mut out)).await?;
+                    Ok(out)
+                })),
+            ));
+            Ok(())
+        })
+    }
+}
+
+/// Renders a view tree with [`Async`] leaves.
+///
+/// The sync parts are rendered first, then all async leaves resolve concurrently
+/// and their output is put back in document order.
+pub fn render_async<V: View + ?Sized>(
+    view: &V,
+) -> impl Future<Output = Result<String, fmt::Error>> + '_ {
+    Resolve::new(async move {
+        let mut out = String::with_capacity(view.text_size());
+        view.render(&mut SlotWriter(&mut out))?;
+        Ok(out)
+    })
+}
+
+// Polls `render` with a frame collecting its async leaves, then resolves them and fills their slots.
+struct Resolve<F> {
+    render: Option<Pin<Box<F>>>,
+    written: usize, // output of the render between polls
+    out: String,
+    slots: Vec<(usize, Slot)>,
+}
+
+enum Slot {
+    Pending(SlotFuture),
+    Ready(String),
+}
+
+impl<F: Future<Output = Result<String, fmt::Error>>> Resolve<F> {
+    fn new(render: F) -> Self {
+        Self {
+            render: Some(Box::pin(render)),
+            written: 0,
+            out: String::new(),
+            slots: Vec::new(),
+        }
+    }
+
+    fn fill_slots(&mut self) -> String {
+        let mut out = String::with_capacity(self.out.len());
+        let mut rest = 0;
+
+        for (offset, slot) in &mut self.slots {
+            out.push_str(&self.out[rest..*offset]);
+            if let Slot::Ready(text) = slot {
+                out.push_str(&mem::take(text));
+            }
+            rest = *offset;
+        }
+
+        out.push_str(&self.out[rest..]);
+        out
+    }
+}
+
+impl<F: Future<Output = Result<String, fmt::Error>>> Future for Resolve<F> {
+    type Output = Result<String, fmt::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Some(render) = &mut this.render {
+            FRAMES.with(|frames| {
+                frames.borrow_mut().push(Frame {
+                    written: this.written,
+                    ..Frame::default()
+                })
+            });
+            let poll = render.as_mut().poll(cx);
+            let frame = FRAMES.with(|frames| frames.borrow_mut().pop().unwrap_or_default());
+            this.written = frame.written;
+            this.slots.extend(
+                frame
+                    .slots
+                    .into_iter()
+                    .map(|(offset, future)| (offset, Slot::Pending(future))),
+            );
+
+            match poll {
+                Poll::Ready(Ok(out)) => {
+                    this.out = out;
+                    this.render = None;
+                }
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        let mut pending = false;
+        for (_, slot) in &mut this.slots {
+            if let Slot::Pending(future) = slot {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(Ok(text)) => *slot = Slot::Ready(text),
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(this.fill_slots()))
+        }
+    }
+}
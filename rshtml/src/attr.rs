@@ -0,0 +1,202 @@
+use crate::{
+    Context, Escaper,
+    escaping_writer::QuoteEscapingWriter,
+    traits::{AttributeValue, View},
+};
+use std::fmt::{self, Display};
+
+/// An expression used as an attribute value in `v!`, e.g. `class={classes}`.
+///
+/// Values implementing [`AttributeValue`] decide how the attribute is rendered,
+/// any other `Display` value is rendered as a quoted value escaped for the attribute,
+/// see [`Context::attribute_value`], and any other view, e.g. a `Vec` or a `Box<dyn View>`,
+/// through [`AttrView`], called on a `&Attr`.
+#[derive(Debug)]
+pub struct Attr<T: ?Sized>(pub T);
+
+/// An attribute that is written, as [`AttributeValue::to_attribute`] returns it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttrValue {
+    /// Only the name, e.g. `disabled`.
+    Bare,
+    /// A value escaped for a double-quoted attribute.
+    Value(String),
+}
+
+impl<T: AttributeValue> Attr<T> {
+    pub fn to_attribute(
+        &self,
+        name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        self.0.to_attribute(name, escaper)
+    }
+
+    pub fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.0.render_attribute(name, out)
+    }
+
+    pub fn render_attribute_escaped(
+        &self,
+        name: &str,
+        escaper: &dyn Escaper,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        self.0.render_attribute_escaped(name, escaper, out)
+    }
+}
+
+impl<T: Display> AttributeValue for Attr<T> {
+    fn to_attribute(
+        &self,
+        name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        escape_attribute(name, &self.0, escaper).map(Some)
+    }
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        write_attribute(name, &self.0, out)
+    }
+
+    fn render_attribute_escaped(
+        &self,
+        name: &str,
+        escaper: &dyn Escaper,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        write_attribute_escaped(name, &self.0, escaper, out)
+    }
+}
+
+#[doc(hidden)]
+pub trait AttrView {
+    fn to_attribute(
+        &self,
+        name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error>;
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result;
+
+    fn render_attribute_escaped(
+        &self,
+        name: &str,
+        escaper: &dyn Escaper,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result;
+}
+
+// The view output is already escaped, so only its double quotes are.
+impl<T: View> AttrView for &Attr<T> {
+    fn to_attribute(
+        &self,
+        _name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        let mut value = String::new();
+        let out = &mut QuoteEscapingWriter { inner: &mut value };
+        match escaper {
+            Some(escaper) => self.0.render_escaped(escaper, out)?,
+            None => self.0.render(out)?,
+        }
+        Ok(Some(AttrValue::Value(value)))
+    }
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        write!(out, "{name}=\"")?;
+        self.0.render(&mut QuoteEscapingWriter { inner: out })?;
+        out.write_char('"')
+    }
+
+    fn render_attribute_escaped(
+        &self,
+        name: &str,
+        escaper: &dyn Escaper,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        write!(out, "{name}=\"")?;
+        self.0
+            .render_escaped(escaper, &mut QuoteEscapingWriter { inner: out })?;
+        out.write_char('"')
+    }
+}
+
+/// Writes `separator` before the first output of an attribute, so an attribute that
+/// is dropped, e.g. `disabled={false}`, also drops the whitespace in front of it.
+#[doc(hidden)]
+pub struct AttrSeparator<'a> {
+    separator: Option<&'a str>,
+    inner: &'a mut dyn fmt::Write,
+}
+
+impl<'a> AttrSeparator<'a> {
+    pub fn new(separator: &'a str, inner: &'a mut dyn fmt::Write) -> Self {
+        Self {
+            separator: Some(separator),
+            inner,
+        }
+    }
+}
+
+impl fmt::Write for AttrSeparator<'_> {
+    fn write_str(&mut self, input: &str) -> fmt::Result {
+        if input.is_empty() {
+            return Ok(());
+        }
+        if let Some(separator) = self.separator.take() {
+            self.inner.write_str(separator)?;
+        }
+        self.inner.write_str(input)
+    }
+}
+
+// Writes `name="value"`, escaping the value for the context of the attribute.
+pub(crate) fn write_attribute(
+    name: &str,
+    value: &dyn Display,
+    out: &mut dyn fmt::Write,
+) -> fmt::Result {
+    write!(out, "{name}=\"")?;
+    Context::attribute_value(name).write(out, |out| write!(out, "{value}"))?;
+    out.write_char('"')
+}
+
+// Writes `name="value"`, escaping the value with the escaper of a `v!(escape = .., ...)`.
+pub(crate) fn write_attribute_escaped(
+    name: &str,
+    value: &dyn Display,
+    escaper: &dyn Escaper,
+    out: &mut dyn fmt::Write,
+) -> fmt::Result {
+    write!(out, "{name}=\"")?;
+    Escaper::write(&escaper, out, |out| write!(out, "{value}"))?;
+    out.write_char('"')
+}
+
+// The value escaped for the context of the attribute, or by `escaper` when it is set.
+pub(crate) fn escape_attribute(
+    name: &str,
+    value: &dyn Display,
+    escaper: Option<&dyn Escaper>,
+) -> Result<AttrValue, fmt::Error> {
+    let mut escaped = String::new();
+    match escaper {
+        Some(escaper) => Escaper::write(&escaper, &mut escaped, |out| write!(out, "{value}"))?,
+        None => Context::attribute_value(name).write(&mut escaped, |out| write!(out, "{value}"))?,
+    }
+    Ok(AttrValue::Value(escaped))
+}
+
+// Writes `name="value"` or `name`, nothing for an omitted attribute.
+pub(crate) fn write_attr_value(
+    name: &str,
+    value: Option<&AttrValue>,
+    out: &mut dyn fmt::Write,
+) -> fmt::Result {
+    match value {
+        Some(AttrValue::Value(value)) => write!(out, "{name}=\"{value}\""),
+        Some(AttrValue::Bare) => out.write_str(name),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,209 @@
+use crate::{
+    AttrValue,
+    escaping_writer::QuoteEscapingWriter,
+    traits::{AttributeValue, View},
+};
+use std::fmt::{self, Write};
+
+/// An ordered set of html attributes, which can be spread into a `v!` tag with `{..attrs}`.
+///
+/// Values follow the same rules as `v!` attribute expressions: `true` renders only the name,
+/// `false` and `None` leave the attribute out, anything else is escaped and quoted.
+///
+/// ```
+/// use rshtml::{Attributes, v};
+///
+/// let attrs = Attributes::new().with("class", "primary").with("disabled", true);
+/// let button = v!(<button class="btn" {..attrs}>Save</button>);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Attributes {
+    attributes: Vec<(String, AttrValue)>,
+    // An attribute value failed to render, which is reported when the attributes are.
+    failed: bool,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: impl Into<String>, value: impl AttributeValue) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    /// Sets the attribute, keeping its position if it is already present.
+    ///
+    /// A name that is not a valid html attribute name, e.g. one with whitespace, `"`, `>`
+    /// or `=` in it, or a value that fails to render is left out and rendering the attributes fails.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl AttributeValue) {
+        let name = name.into();
+        if !is_attribute_name(&name) {
+            self.failed = true;
+            return;
+        }
+
+        match value.to_attribute(&name, None) {
+            Ok(Some(value)) => self.set(name, value),
+            Ok(None) => {
+                self.remove(&name);
+            }
+            Err(fmt::Error) => self.failed = true,
+        }
+    }
+
+    /// Returns the escaped value of the attribute, `Some(None)` for an attribute without a value.
+    pub fn get(&self, name: &str) -> Option<Option<&str>> {
+        self.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.attributes.iter().any(|(n, _)| n == name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.attributes.len();
+        self.attributes.retain(|(n, _)| n != name);
+        self.attributes.len() != len
+    }
+
+    pub fn len(&self) -> usize {
+        self.attributes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.attributes.iter().map(|(name, value)| {
+            let value = match value {
+                AttrValue::Value(value) => Some(value.as_str()),
+                AttrValue::Bare => None,
+            };
+            (name.as_str(), value)
+        })
+    }
+
+    /// Merges `other` into these attributes, `class` values are joined and
+    /// any other attribute of `other` replaces the existing one.
+    pub fn merge(&mut self, other: &Attributes) {
+        for (name, value) in &other.attributes {
+            self.merge_value(name, Some(value.clone()));
+        }
+        self.failed |= other.failed;
+    }
+
+    /// Merges a literal attribute of the tag, used by `v!`. The value is html source,
+    /// so only the quotes that would end it between double quotes are escaped.
+    #[doc(hidden)]
+    pub fn merge_html(&mut self, name: &str, value: Option<&str>) {
+        let value = match value {
+            Some(value) => {
+                let mut escaped = String::with_capacity(value.len());
+                QuoteEscapingWriter {
+                    inner: &mut escaped,
+                }
+                .write_str(value)
+                .expect("writing to a String does not fail");
+                AttrValue::Value(escaped)
+            }
+            None => AttrValue::Bare,
+        };
+
+        self.merge_value(name, Some(value));
+    }
+
+    /// Merges the value of an attribute expression, used by `v!`.
+    /// An omitted attribute removes the existing one, except for `class`.
+    #[doc(hidden)]
+    pub fn merge_value(&mut self, name: &str, value: Option<AttrValue>) {
+        if !is_attribute_name(name) {
+            self.failed = true;
+            return;
+        }
+
+        let existing = self.attributes.iter_mut().find(|(n, _)| n == name);
+
+        match (value, existing) {
+            (Some(value), Some((_, AttrValue::Value(class)))) if name == "class" => {
+                if let AttrValue::Value(value) = value
+                    && !value.is_empty()
+                {
+                    if !class.is_empty() {
+                        class.push(' ');
+                    }
+                    class.push_str(&value);
+                }
+            }
+            (Some(value), _) => self.set(name.to_owned(), value),
+            (None, _) if name != "class" => {
+                self.remove(name);
+            }
+            (None, _) => (),
+        }
+    }
+
+    fn set(&mut self, name: String, value: AttrValue) {
+        match self.attributes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.attributes.push((name, value)),
+        }
+    }
+}
+
+// The attribute name grammar of html, no controls, whitespace, quotes, `>`, `/`, `=` or noncharacters,
+// and no `<`, which browsers accept but is a parse error.
+fn is_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().all(|c| {
+            !c.is_control()
+                && !c.is_whitespace()
+                && !matches!(c, '"' | '\'' | '>' | '/' | '=' | '<')
+                && !matches!(c as u32, 0xFDD0..=0xFDEF)
+                && (c as u32 & 0xFFFE) != 0xFFFE
+        })
+}
+
+impl<K: Into<String>, V: AttributeValue> FromIterator<(K, V)> for Attributes {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut attributes = Self::new();
+        attributes.extend(iter);
+        attributes
+    }
+}
+
+impl<K: Into<String>, V: AttributeValue> Extend<(K, V)> for Attributes {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+/// Renders every attribute with a leading space, e.g. ` class="btn" disabled`.
+impl View for Attributes {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        if self.failed {
+            return Err(fmt::Error);
+        }
+
+        for (name, value) in self.iter() {
+            match value {
+                Some(value) => write!(out, " {name}=\"{value}\"")?,
+                None => write!(out, " {name}")?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn text_size(&self) -> usize {
+        self.iter()
+            .map(|(name, value)| 1 + name.len() + value.map_or(0, |value| value.len() + 3))
+            .sum()
+    }
+}
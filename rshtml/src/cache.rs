@@ -0,0 +1,237 @@
+//! Fragment caching for subtrees that render the same output many times,
+//! e.g. a navigation bar, a footer or a product card.
+//!
+//! ```
+//! use rshtml::{cache::Cached, traits::View, v};
+//! use std::time::Duration;
+//!
+//! let year = 2025;
+//! let footer = Cached::new("footer", v!(@lazy <footer>{year}</footer>)).ttl(Duration::from_secs(60));
+//!
+//! let mut out = String::new();
+//! footer.render(&mut out).unwrap();
+//! ```
+
+use crate::{async_view, traits::View};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// Where `Cached` views keep their output.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<Arc<str>>;
+
+    /// Reads the output without side effects such as marking it recently used,
+    /// e.g. to size a buffer. Stores that cannot do this return `None`.
+    fn peek(&self, key: &str) -> Option<Arc<str>> {
+        let _ = key;
+        None
+    }
+
+    /// Stores the rendered output, to expire after `ttl` when it is given.
+    fn insert(&self, key: &str, value: Arc<str>, ttl: Option<Duration>);
+
+    fn remove(&self, key: &str);
+}
+
+/// An in-process least recently used store, the default for `Cached`.
+pub struct LruStore {
+    capacity: usize,
+    entries: Mutex<Lru>,
+}
+
+// The entries with an index of their keys by last use, so the least recently used
+// one is found without a scan.
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, Entry>,
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) -> Option<&mut Entry> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(key)?;
+        let key = self.order.remove(&entry.used)?;
+        entry.used = self.tick;
+        self.order.insert(self.tick, key);
+        Some(entry)
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.used);
+        Some(entry)
+    }
+}
+
+struct Entry {
+    value: Arc<str>,
+    expires: Option<Instant>,
+    used: u64,
+}
+
+impl LruStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Lru::default()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut lru = self.lock();
+        lru.entries.clear();
+        lru.order.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        // The entries are always left consistent, so a poisoned lock is still usable.
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl CacheStore for LruStore {
+    fn get(&self, key: &str) -> Option<Arc<str>> {
+        let mut lru = self.lock();
+
+        let entry = lru.touch(key)?;
+        if entry
+            .expires
+            .is_some_and(|expires| expires <= Instant::now())
+        {
+            lru.remove(key);
+            return None;
+        }
+
+        Some(entry.value.clone())
+    }
+
+    fn peek(&self, key: &str) -> Option<Arc<str>> {
+        let lru = self.lock();
+        let entry = lru.entries.get(key)?;
+        let expired = entry
+            .expires
+            .is_some_and(|expires| expires <= Instant::now());
+
+        (!expired).then(|| entry.value.clone())
+    }
+
+    fn insert(&self, key: &str, value: Arc<str>, ttl: Option<Duration>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut lru = self.lock();
+        lru.remove(key);
+
+        if lru.entries.len() >= self.capacity
+            && let Some((_, oldest)) = lru.order.pop_first()
+        {
+            lru.entries.remove(&oldest);
+        }
+
+        lru.tick += 1;
+        let used = lru.tick;
+        lru.order.insert(used, key.to_owned());
+
+        let expires = ttl.map(|ttl| Instant::now() + ttl);
+        lru.entries.insert(
+            key.to_owned(),
+            Entry {
+                value,
+                expires,
+                used,
+            },
+        );
+    }
+
+    fn remove(&self, key: &str) {
+        self.lock().remove(key);
+    }
+}
+
+/// The store used when no other one is given, holding up to 1024 fragments.
+pub fn default_store() -> Arc<dyn CacheStore> {
+    static STORE: OnceLock<Arc<LruStore>> = OnceLock::new();
+    STORE.get_or_init(|| Arc::new(LruStore::new(1024))).clone()
+}
+
+/// A view rendered once and replayed from the store under its key on later renders.
+///
+/// The view is only rendered on a miss, so expressions that are expensive to evaluate
+/// should be inside a `v!(@lazy ..)` view. The key must identify everything the output depends on.
+pub struct Cached<V> {
+    key: String,
+    view: V,
+    ttl: Option<Duration>,
+    store: Arc<dyn CacheStore>,
+}
+
+impl<V> Cached<V> {
+    pub fn new(key: impl Into<String>, view: V) -> Self {
+        Self {
+            key: key.into(),
+            view,
+            ttl: None,
+            store: default_store(),
+        }
+    }
+
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn store(mut self, store: Arc<dyn CacheStore>) -> Self {
+        self.store = store;
+        self
+    }
+}
+
+impl<V: View> View for Cached<V> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        render_cached(&*self.store, &self.key, self.ttl, out, |out| {
+            self.view.render(out)
+        })
+    }
+
+    /// The length of the cached output, or the estimate of the view before it is cached.
+    fn text_size(&self) -> usize {
+        self.store
+            .peek(&self.key)
+            .map_or_else(|| self.view.text_size(), |cached| cached.len())
+    }
+}
+
+/// Writes the output cached under `key`, rendering and storing it on a miss.
+pub fn render_cached(
+    store: &dyn CacheStore,
+    key: &str,
+    ttl: Option<Duration>,
+    out: &mut dyn fmt::Write,
+    render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+) -> fmt::Result {
+    if let Some(cached) = store.get(key) {
+        return out.write_str(&cached);
+    }
+
+    let mut rendered = String::new();
+    async_view::buffered(|| render(&mut rendered))?;
+    out.write_str(&rendered)?;
+    store.insert(key, rendered.into(), ttl);
+
+    Ok(())
+}
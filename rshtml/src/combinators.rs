@@ -0,0 +1,160 @@
+use crate::{async_view, traits::View, view_iter::size_hint};
+use std::{cell::RefCell, fmt, sync::OnceLock};
+
+/// Renders the view only when the condition holds, created with `ViewExt::when`.
+#[derive(Debug, Clone)]
+pub struct When<V>(pub V, pub bool);
+
+impl<V: View> View for When<V> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        if self.1 { self.0.render(out) } else { Ok(()) }
+    }
+
+    fn text_size(&self) -> usize {
+        if self.1 { self.0.text_size() } else { 0 }
+    }
+}
+
+/// Renders the second view when the first one renders nothing, created with `ViewExt::or_else_view`.
+#[derive(Debug, Clone)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<A: View, B: View> View for Or<A, B> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        let mut tracking = TrackingWriter {
+            inner: out,
+            written: false,
+        };
+        // An async leaf renders later, but it is output all the same.
+        let slots = async_view::slot_count();
+        self.0.render(&mut tracking)?;
+
+        if tracking.written || async_view::slot_count() > slots {
+            Ok(())
+        } else {
+            self.1.render(out)
+        }
+    }
+
+    fn text_size(&self) -> usize {
+        match self.0.text_size() {
+            0 => self.1.text_size(),
+            size => size,
+        }
+    }
+}
+
+struct TrackingWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    written: bool,
+}
+
+impl fmt::Write for TrackingWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.written |= !s.is_empty();
+        self.inner.write_str(s)
+    }
+}
+
+/// Renders the view inside an element, created with `ViewExt::wrap_in`.
+#[derive(Debug, Clone)]
+pub struct WrapIn<V, A> {
+    pub tag: &'static str,
+    pub attributes: A,
+    pub view: V,
+}
+
+impl<V: View, A: View> View for WrapIn<V, A> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        write!(out, "<{}", self.tag)?;
+        self.attributes.render(out)?;
+        out.write_char('>')?;
+        self.view.render(out)?;
+        write!(out, "</{}>", self.tag)
+    }
+
+    fn text_size(&self) -> usize {
+        2 * self.tag.len() + 5 + self.attributes.text_size() + self.view.text_size()
+    }
+}
+
+/// Renders the views of an iterator with a separator between them, created with `ViewIterExt::join_views`.
+///
+/// Like `ViewIter`, the iterator is consumed by the first render.
+pub struct Join<I, S> {
+    iter: RefCell<Option<I>>,
+    separator: S,
+}
+
+impl<I, S> Join<I, S> {
+    pub fn new(iter: I, separator: S) -> Self {
+        Self {
+            iter: RefCell::new(Some(iter)),
+            separator,
+        }
+    }
+}
+
+impl<I, V, S> View for Join<I, S>
+where
+    I: Iterator<Item = V>,
+    V: View,
+    S: View,
+{
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        let iter = self.iter.borrow_mut().take().ok_or(fmt::Error)?;
+        for (i, item) in iter.enumerate() {
+            if i > 0 {
+                self.separator.render(out)?;
+            }
+            item.render(out)?;
+        }
+        Ok(())
+    }
+
+    fn text_size(&self) -> usize {
+        let iter = self.iter.borrow();
+        let Some(iter) = iter.as_ref() else {
+            return 0;
+        };
+
+        let separators = iter.size_hint().0.saturating_sub(1);
+        size_hint(iter).saturating_add(separators.saturating_mul(self.separator.text_size()))
+    }
+}
+
+/// Caches the rendered output of a view, created with `ViewExt::memo`.
+///
+/// The view is rendered on the first render only, a failed render is retried the next time.
+#[derive(Debug)]
+pub struct Memo<V> {
+    view: V,
+    rendered: OnceLock<String>,
+}
+
+impl<V> Memo<V> {
+    pub fn new(view: V) -> Self {
+        Self {
+            view,
+            rendered: OnceLock::new(),
+        }
+    }
+}
+
+impl<V: View> View for Memo<V> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        if let Some(rendered) = self.rendered.get() {
+            return out.write_str(rendered);
+        }
+
+        let mut rendered = String::with_capacity(self.view.text_size());
+        async_view::buffered(|| self.view.render(&mut rendered))?;
+        out.write_str(self.rendered.get_or_init(|| rendered))
+    }
+
+    fn text_size(&self) -> usize {
+        self.rendered
+            .get()
+            .map_or_else(|| self.view.text_size(), String::len)
+    }
+}
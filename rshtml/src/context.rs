@@ -0,0 +1,152 @@
+use crate::{
+    async_view,
+    escaper::Escaper,
+    escaping_writer::{
+        AttributeEscapingWriter, CssEscapingWriter, EscapingWriter, JsEscapingWriter,
+        UnquotedEscapingWriter,
+    },
+};
+use std::fmt::{self, Write};
+
+/// Where an interpolated value is written in the html, deciding how it is escaped.
+///
+/// The template compilers pick the context of every expression from its position,
+/// e.g. `<a href="@url">` is a `Url` and `<script>let x = "@name";</script>` is `Js`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    /// Element content.
+    Text,
+    /// A quoted attribute value.
+    Attribute,
+    /// The start of a url attribute value such as `href` or `src`,
+    /// a url with a scheme other than http, https, mailto or tel is replaced by `about:invalid`.
+    Url,
+    /// A javascript string, in a `<script>` element or an `on*` event handler attribute.
+    Js,
+    /// A css string or identifier, in a `<style>` element or a `style` attribute.
+    Css,
+}
+
+const URL_ATTRIBUTES: [&str; 13] = [
+    "action",
+    "background",
+    "cite",
+    "codebase",
+    "data",
+    "formaction",
+    "href",
+    "icon",
+    "longdesc",
+    "manifest",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+const SAFE_URL_SCHEMES: [&str; 4] = ["http", "https", "mailto", "tel"];
+
+const INVALID_URL: &str = "about:invalid";
+
+impl Context {
+    /// The context of a value in the attribute `name`, `at_start` when no part
+    /// of the attribute value is written before it.
+    pub fn attribute(name: &str, at_start: bool) -> Self {
+        let name = name.trim().to_ascii_lowercase();
+
+        if name.starts_with("on") {
+            Self::Js
+        } else if name == "style" {
+            Self::Css
+        } else if at_start && URL_ATTRIBUTES.contains(&name.as_str()) {
+            Self::Url
+        } else {
+            Self::Attribute
+        }
+    }
+
+    /// The context of an expression that is the whole value of the attribute `name`,
+    /// e.g. `v!(<a href={url}>)`. Urls are still checked, but a whole `style` or `on*`
+    /// value is the css or script itself, so it is only escaped for the attribute.
+    pub fn attribute_value(name: &str) -> Self {
+        match Self::attribute(name, true) {
+            Self::Url => Self::Url,
+            _ => Self::Attribute,
+        }
+    }
+}
+
+impl Escaper for Context {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Self::Text => EscapingWriter { inner: out }.write_str(input),
+            Self::Attribute | Self::Url => AttributeEscapingWriter { inner: out }.write_str(input),
+            Self::Js => JsEscapingWriter { inner: out }.write_str(input),
+            Self::Css => CssEscapingWriter { inner: out }.write_str(input),
+        }
+    }
+
+    /// Writes the output of `render` escaped for this context.
+    fn write(
+        &self,
+        out: &mut dyn fmt::Write,
+        render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> fmt::Result {
+        match self {
+            Self::Text => render(&mut EscapingWriter { inner: out }),
+            Self::Attribute => render(&mut AttributeEscapingWriter { inner: out }),
+            Self::Js => render(&mut JsEscapingWriter { inner: out }),
+            Self::Css => render(&mut CssEscapingWriter { inner: out }),
+            Self::Url => {
+                // The scheme is only known once the whole url is rendered.
+                let mut url = String::new();
+                async_view::buffered(|| render(&mut url))?;
+                AttributeEscapingWriter { inner: out }.write_str(safe_url(&url))
+            }
+        }
+    }
+}
+
+/// The context `E` of an unquoted attribute value such as `<a title=@title>`,
+/// whitespace, `=`, quotes and backticks are escaped as well so the value cannot end early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unquoted<E>(pub E);
+
+impl<E: Escaper> Escaper for Unquoted<E> {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.0
+            .escape_str(input, &mut UnquotedEscapingWriter { inner: out })
+    }
+
+    fn write(
+        &self,
+        out: &mut dyn fmt::Write,
+        render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> fmt::Result {
+        self.0
+            .write(&mut UnquotedEscapingWriter { inner: out }, render)
+    }
+}
+
+// Relative urls and urls with a known safe scheme are kept, browsers ignore leading
+// whitespace and tabs or newlines inside the scheme, so they are skipped here as well.
+fn safe_url(url: &str) -> &str {
+    let mut scheme = String::new();
+
+    for c in url.trim_start_matches(|c: char| c <= ' ').chars() {
+        match c {
+            ':' => {
+                let scheme = scheme.to_ascii_lowercase();
+                return if SAFE_URL_SCHEMES.contains(&scheme.as_str()) {
+                    url
+                } else {
+                    INVALID_URL
+                };
+            }
+            '/' | '?' | '#' => return url,
+            '\t' | '\n' | '\r' => (),
+            c => scheme.push(c),
+        }
+    }
+
+    url
+}
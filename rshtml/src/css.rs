@@ -0,0 +1,21 @@
+use crate::{Context, Escaper, SafeHtml, traits::View};
+use std::fmt::{self, Display};
+
+/// An expression inside a `<style>` element in `v!`, escaped for a css string
+/// or identifier, e.g. `".theme-" {theme} " { color: red; }"`.
+///
+/// `SafeHtml` values such as a `Markup` are written as they are.
+#[derive(Debug)]
+pub struct Css<T: ?Sized>(pub T);
+
+impl<T: SafeHtml> Css<T> {
+    pub fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.0.write_html(out)
+    }
+}
+
+impl<T: Display> View for Css<T> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        Context::Css.write(out, |out| write!(out, "{}", &self.0))
+    }
+}
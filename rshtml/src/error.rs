@@ -0,0 +1,44 @@
+use std::{error, fmt, io};
+
+/// An error of the byte oriented renderers, e.g. `View::render_to_io`.
+#[derive(Debug)]
+pub enum Error {
+    /// A view or a `Display` value failed to format.
+    Fmt(fmt::Error),
+    /// The underlying writer failed.
+    Io(io::Error),
+    /// The view panicked while it was rendered on another thread, e.g. by `into_chunks`.
+    Panic,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Fmt(_) => f.write_str("failed to render the view"),
+            Error::Io(err) => write!(f, "failed to write the rendered view: {err}"),
+            Error::Panic => f.write_str("the view panicked while rendering"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Fmt(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::Panic => None,
+        }
+    }
+}
+
+impl From<fmt::Error> for Error {
+    fn from(err: fmt::Error) -> Self {
+        Error::Fmt(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
@@ -0,0 +1,125 @@
+//! How expressions are escaped for the output format of a template.
+//!
+//! Templates escape for html by default, following the [`Context`](crate::Context) of every
+//! expression. Other formats are chosen with `#[rshtml(escape = "xml")]`, a template extension
+//! like `feed.rs.xml` or `mail.rs.txt`, or `v!(escape = xml, ...)`.
+//!
+//! ```
+//! use rshtml::{traits::View, v};
+//!
+//! let title = "Tom & Jerry";
+//! let item = v!(escape = xml, <item><title>{title}</title></item>);
+//!
+//! assert_eq!(item.render_to_string().unwrap(), "<item><title>Tom &amp; Jerry</title></item>");
+//! ```
+
+use crate::escaping_writer::{ByteEscapes, HTML_ESCAPES};
+use std::fmt;
+
+/// Escapes the output of expressions, implemented by the built-in formats
+/// and by `Context` for the parts of an html document.
+pub trait Escaper {
+    /// Writes `input` escaped, called for every piece of a value as it is written.
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result;
+
+    /// Writes the output of `render` escaped.
+    fn write(
+        &self,
+        out: &mut dyn fmt::Write,
+        render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> fmt::Result
+    where
+        Self: Sized,
+    {
+        render(&mut EscaperWriter { escaper: self, out })
+    }
+}
+
+impl<E: Escaper + ?Sized> Escaper for &E {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        (*self).escape_str(input, out)
+    }
+}
+
+struct EscaperWriter<'a, 'b, E: ?Sized> {
+    escaper: &'a E,
+    out: &'a mut (dyn fmt::Write + 'b),
+}
+
+impl<E: Escaper + ?Sized> fmt::Write for EscaperWriter<'_, '_, E> {
+    fn write_str(&mut self, input: &str) -> fmt::Result {
+        self.escaper.escape_str(input, self.out)
+    }
+}
+
+/// Html text, `&<>"'/` are written as character references.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Html;
+
+impl Escaper for Html {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        HTML_ESCAPES.write(out, input)
+    }
+}
+
+/// Xml text and attribute values, `&<>"'` are written as the predefined entities.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Xml;
+
+const XML_ESCAPES: ByteEscapes = ByteEscapes::new(&[
+    (b'&', "&amp;"),
+    (b'<', "&lt;"),
+    (b'>', "&gt;"),
+    (b'"', "&quot;"),
+    (b'\'', "&apos;"),
+]);
+
+impl Escaper for Xml {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        XML_ESCAPES.write(out, input)
+    }
+}
+
+/// Plain text such as an email body, written as it is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Text;
+
+impl Escaper for Text {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        out.write_str(input)
+    }
+}
+
+/// The contents of a json string, e.g. `{"name": "@self.name"}`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+impl Escaper for Json {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        let mut run = 0;
+
+        for (i, byte) in input.bytes().enumerate() {
+            let escaped = match byte {
+                b'"' => "\\\"",
+                b'\\' => "\\\\",
+                b'\n' => "\\n",
+                b'\r' => "\\r",
+                b'\t' => "\\t",
+                0x08 => "\\b",
+                0x0c => "\\f",
+                0x00..=0x1f => "",
+                _ => continue,
+            };
+
+            out.write_str(&input[run..i])?;
+            if escaped.is_empty() {
+                write!(out, "\\u{byte:04x}")?;
+            } else {
+                out.write_str(escaped)?;
+            }
+            run = i + 1;
+        }
+
+        out.write_str(&input[run..])
+    }
+}
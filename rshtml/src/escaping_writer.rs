@@ -3,15 +3,62 @@ pub struct EscapingWriter<'a, T: ::std::fmt::Write + ?Sized> {
 }
 
 impl<'a, T: ::std::fmt::Write + ?Sized> ::std::fmt::Write for EscapingWriter<'a, T> {
+    fn write_str(&mut self, input: &str) -> ::std::fmt::Result {
+        HTML_ESCAPES.write(self.inner, input)
+    }
+}
+
+/// Escapes text for a double-quoted attribute value.
+pub(crate) struct AttributeEscapingWriter<'a, T: ::std::fmt::Write + ?Sized> {
+    pub inner: &'a mut T,
+}
+
+impl<'a, T: ::std::fmt::Write + ?Sized> ::std::fmt::Write for AttributeEscapingWriter<'a, T> {
+    fn write_str(&mut self, input: &str) -> ::std::fmt::Result {
+        ATTRIBUTE_ESCAPES.write(self.inner, input)
+    }
+}
+
+/// Escapes only the double quotes of already escaped html, e.g. a rendered view.
+pub(crate) struct QuoteEscapingWriter<'a, T: ::std::fmt::Write + ?Sized> {
+    pub inner: &'a mut T,
+}
+
+impl<'a, T: ::std::fmt::Write + ?Sized> ::std::fmt::Write for QuoteEscapingWriter<'a, T> {
+    fn write_str(&mut self, input: &str) -> ::std::fmt::Result {
+        QUOTE_ESCAPES.write(self.inner, input)
+    }
+}
+
+/// Escapes what ends an unquoted attribute value in text that is already escaped
+/// for the attribute, e.g. the spaces of a value written as `<a title=@title>`.
+pub(crate) struct UnquotedEscapingWriter<'a, T: ::std::fmt::Write + ?Sized> {
+    pub inner: &'a mut T,
+}
+
+impl<'a, T: ::std::fmt::Write + ?Sized> ::std::fmt::Write for UnquotedEscapingWriter<'a, T> {
+    fn write_str(&mut self, input: &str) -> ::std::fmt::Result {
+        UNQUOTED_ESCAPES.write(self.inner, input)
+    }
+}
+
+/// Escapes text for a javascript string or template literal inside a `<script>` element.
+pub(crate) struct JsEscapingWriter<'a, T: ::std::fmt::Write + ?Sized> {
+    pub inner: &'a mut T,
+}
+
+impl<'a, T: ::std::fmt::Write + ?Sized> ::std::fmt::Write for JsEscapingWriter<'a, T> {
     fn write_str(&mut self, input: &str) -> ::std::fmt::Result {
         for c in input.chars() {
             match c {
-                '&' => self.inner.write_str("&amp;")?,
-                '<' => self.inner.write_str("&lt;")?,
-                '>' => self.inner.write_str("&gt;")?,
-                '"' => self.inner.write_str("&quot;")?,
-                '\'' => self.inner.write_str("&#39;")?,
-                '/' => self.inner.write_str("&#x2F;")?,
+                '\\' => self.inner.write_str("\\\\")?,
+                '\n' => self.inner.write_str("\\n")?,
+                '\r' => self.inner.write_str("\\r")?,
+                '\t' => self.inner.write_str("\\t")?,
+                '"' | '\'' | '`' | '<' | '>' | '&' | '/' | '$' | '\u{2028}' | '\u{2029}' => {
+                    write!(self.inner, "\\u{:04X}", c as u32)?
+                }
+                c if c.is_control() => write!(self.inner, "\\u{:04X}", c as u32)?,
                 _ => self.inner.write_char(c)?,
             }
         }
@@ -19,3 +66,147 @@ impl<'a, T: ::std::fmt::Write + ?Sized> ::std::fmt::Write for EscapingWriter<'a,
         Ok(())
     }
 }
+
+/// Escapes text for a css string or identifier inside a `<style>` element.
+pub(crate) struct CssEscapingWriter<'a, T: ::std::fmt::Write + ?Sized> {
+    pub inner: &'a mut T,
+}
+
+impl<'a, T: ::std::fmt::Write + ?Sized> ::std::fmt::Write for CssEscapingWriter<'a, T> {
+    fn write_str(&mut self, input: &str) -> ::std::fmt::Result {
+        for c in input.chars() {
+            match c {
+                c if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' => {
+                    self.inner.write_char(c)?
+                }
+                // The trailing space ends the hex escape, it is not part of the value.
+                c => write!(self.inner, "\\{:X} ", c as u32)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) const HTML_ESCAPES: ByteEscapes = ByteEscapes::new(&[
+    (b'&', "&amp;"),
+    (b'<', "&lt;"),
+    (b'>', "&gt;"),
+    (b'"', "&quot;"),
+    (b'\'', "&#39;"),
+    (b'/', "&#x2F;"),
+]);
+
+const ATTRIBUTE_ESCAPES: ByteEscapes = ByteEscapes::new(&[
+    (b'&', "&amp;"),
+    (b'<', "&lt;"),
+    (b'>', "&gt;"),
+    (b'"', "&quot;"),
+    (b'\'', "&#39;"),
+]);
+
+const UNQUOTED_ESCAPES: ByteEscapes = ByteEscapes::new(&[
+    (b' ', "&#32;"),
+    (b'\t', "&#9;"),
+    (b'\n', "&#10;"),
+    (0x0c, "&#12;"),
+    (b'\r', "&#13;"),
+    (b'=', "&#61;"),
+    (b'`', "&#96;"),
+    (b'<', "&lt;"),
+    (b'>', "&gt;"),
+    (b'"', "&quot;"),
+    (b'\'', "&#39;"),
+]);
+
+const QUOTE_ESCAPES: ByteEscapes = ByteEscapes::new(&[(b'"', "&quot;")]);
+
+/// The replacements of a set of ascii bytes.
+///
+/// The input is scanned 8 bytes at a time for the next escaped byte and every run of
+/// other bytes is written with a single `write_str`. Escaped bytes are ascii, so they
+/// never occur inside a multi-byte char and the runs always end at a char boundary.
+pub(crate) struct ByteEscapes {
+    escapes: &'static [(u8, &'static str)],
+    table: [Option<&'static str>; 256],
+}
+
+const LOW_BITS: u64 = u64::from_ne_bytes([0x01; 8]);
+const HIGH_BITS: u64 = u64::from_ne_bytes([0x80; 8]);
+
+impl ByteEscapes {
+    pub(crate) const fn new(escapes: &'static [(u8, &'static str)]) -> Self {
+        let mut table = [None; 256];
+        let mut i = 0;
+        while i < escapes.len() {
+            assert!(escapes[i].0.is_ascii());
+            table[escapes[i].0 as usize] = Some(escapes[i].1);
+            i += 1;
+        }
+
+        Self { escapes, table }
+    }
+
+    #[inline]
+    pub(crate) fn write<W: ::std::fmt::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        input: &str,
+    ) -> ::std::fmt::Result {
+        let bytes = input.as_bytes();
+        let mut run = 0;
+
+        while let Some(found) = self.find(bytes, run) {
+            if run < found {
+                out.write_str(&input[run..found])?;
+            }
+            if let Some(escaped) = self.table[bytes[found] as usize] {
+                out.write_str(escaped)?;
+            }
+            run = found + 1;
+        }
+
+        if run < bytes.len() {
+            out.write_str(&input[run..])?;
+        }
+
+        Ok(())
+    }
+
+    // The index of the first escaped byte at or after `from`.
+    #[inline]
+    fn find(&self, bytes: &[u8], from: usize) -> Option<usize> {
+        // Escaped bytes often come together, e.g. `</`, which is cheaper to check directly.
+        if self.table[*bytes.get(from)? as usize].is_some() {
+            return Some(from);
+        }
+
+        let mut i = from + 1;
+
+        while let Some(chunk) = bytes.get(i..i + 8) {
+            let word = u64::from_le_bytes(chunk.try_into().unwrap_or_default());
+            let mask = self
+                .escapes
+                .iter()
+                .fold(0, |mask, &(byte, _)| mask | Self::matches(word, byte));
+
+            // Only the lowest flag of a mask is exact, which is the one needed here.
+            if mask != 0 {
+                return Some(i + mask.trailing_zeros() as usize / 8);
+            }
+            i += 8;
+        }
+
+        bytes[i..]
+            .iter()
+            .position(|&b| self.table[b as usize].is_some())
+            .map(|position| i + position)
+    }
+
+    // Sets the high bit of each byte of `word` equal to `byte`, the "has zero byte" trick.
+    #[inline(always)]
+    fn matches(word: u64, byte: u8) -> u64 {
+        let x = word ^ (LOW_BITS * byte as u64);
+        x.wrapping_sub(LOW_BITS) & !x & HIGH_BITS
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{EscapingWriter, traits::View};
-use std::fmt::{self, Debug, Display, Write};
+use crate::{Context, Escaper, SafeHtml, text_size::DYNAMIC_SIZE_HINT, traits::View};
+use std::fmt::{self, Debug, Display};
 
 #[derive(Debug)]
 pub struct Exp<T: ?Sized>(pub T);
@@ -12,7 +12,11 @@ impl<T: View> Exp<T> {
 
 impl<T: Display> View for Exp<T> {
     fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
-        write!(&mut EscapingWriter { inner: out }, "{}", &self.0)
+        Context::Text.write(out, |out| write!(out, "{}", &self.0))
+    }
+
+    fn text_size(&self) -> usize {
+        DYNAMIC_SIZE_HINT
     }
 }
 
@@ -24,3 +28,38 @@ where
         (self.0).render(out)
     }
 }
+
+/// An expression in `v!(escape = .., ...)`, escaped with the given `Escaper` instead of for html.
+///
+/// `SafeHtml` values are written as they are, `Display` values are escaped and any other
+/// view, e.g. a nested `v!` or an `Option`, is rendered with [`View::render_escaped`]
+/// through [`ExpWithView`], called on a `&ExpWith`.
+#[derive(Debug)]
+pub struct ExpWith<E, T: ?Sized>(pub E, pub T);
+
+impl<E, T: SafeHtml> ExpWith<E, T> {
+    pub fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.1.write_html(out)
+    }
+}
+
+impl<E: Escaper, T: Display> View for ExpWith<E, T> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.0.write(out, |out| write!(out, "{}", &self.1))
+    }
+
+    fn text_size(&self) -> usize {
+        DYNAMIC_SIZE_HINT
+    }
+}
+
+#[doc(hidden)]
+pub trait ExpWithView {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+impl<E: Escaper, T: View> ExpWithView for &ExpWith<E, T> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.1.render_escaped(&self.0, out)
+    }
+}
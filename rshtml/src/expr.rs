@@ -52,11 +52,24 @@ where
 }
 
 impl<T: ?Sized> Deref for Expr<T> {
-    type Target = ();
+    type Target = Unrenderable;
 
     #[inline(always)]
     fn deref(&self) -> &Self::Target {
-        &()
+        &Unrenderable
+    }
+}
+
+/// Reached by an `Expr` whose value can't be rendered, reports it when it is rendered.
+///
+/// Its `render` is inherent, so it is not ambiguous with `View` and `Render` in scope.
+#[doc(hidden)]
+pub struct Unrenderable;
+
+impl Unrenderable {
+    pub fn render(&self, _f: &mut dyn fmt::Write, e: &'static str) -> fmt::Result {
+        eprintln!("{e}");
+        Err(fmt::Error)
     }
 }
 
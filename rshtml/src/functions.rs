@@ -1,5 +1,6 @@
 mod time;
 
+use crate::{Markup, PreEscaped};
 use serde::Serialize;
 use std::string::ToString;
 pub use time::*;
@@ -12,10 +13,24 @@ pub fn json<T: Serialize>(value: &T) -> String {
 }
 
 pub fn json_let<T: Serialize>(name: &str, value: &T) -> String {
-    let json = serde_json::to_string(value).unwrap_or_else(|err| {
-        eprintln!("DEBUG: JSON error: {err}");
-        "{}".to_string()
-    });
+    format!("let {name} = {}", json(value))
+}
+
+/// The value as json that is safe to write into a `<script>` element as it is,
+/// e.g. `<script>const users = @json_script(&self.users);</script>`.
+///
+/// Like any `Markup` it is still escaped in attribute values.
+pub fn json_script<T: Serialize>(value: &T) -> Markup {
+    PreEscaped(script_json(value))
+}
 
-    format!("let {name} = {json}")
+// `<`, `>` and `&` can only be inside json strings, where their escapes keep
+// a `</script>` or `<!--` in the value from ending the script.
+fn script_json<T: Serialize>(value: &T) -> String {
+    json(value)
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
 }
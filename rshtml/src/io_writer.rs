@@ -0,0 +1,47 @@
+use crate::Error;
+use std::{
+    fmt,
+    io::{self, BufWriter, Write},
+};
+
+/// Adapts an `io::Write` to `fmt::Write`, keeping the io error that `fmt::Error` can't carry.
+///
+/// The output is buffered, so a `File` or a `TcpStream` is not written once per fragment.
+pub(crate) struct IoWriter<'a> {
+    inner: BufWriter<&'a mut dyn io::Write>,
+    error: Option<io::Error>,
+}
+
+impl<'a> IoWriter<'a> {
+    pub(crate) fn new(inner: &'a mut dyn io::Write) -> Self {
+        Self {
+            inner: BufWriter::new(inner),
+            error: None,
+        }
+    }
+
+    /// Runs `render` against the writer and maps its result, preferring the io error if there is one.
+    pub(crate) fn write(
+        mut self,
+        render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> Result<(), Error> {
+        let result = render(&mut self);
+
+        match self.error {
+            Some(err) => Err(Error::Io(err)),
+            None => {
+                result.map_err(Error::Fmt)?;
+                self.inner.flush().map_err(Error::Io)
+            }
+        }
+    }
+}
+
+impl fmt::Write for IoWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
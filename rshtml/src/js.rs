@@ -0,0 +1,21 @@
+use crate::{Context, Escaper, SafeHtml, traits::View};
+use std::fmt::{self, Display};
+
+/// An expression inside a `<script>` element in `v!`, escaped for a javascript
+/// string literal, e.g. `"const name = '" {name} "';"`.
+///
+/// `SafeHtml` values such as a `Markup` are written as they are.
+#[derive(Debug)]
+pub struct Js<T: ?Sized>(pub T);
+
+impl<T: SafeHtml> Js<T> {
+    pub fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.0.write_html(out)
+    }
+}
+
+impl<T: Display> View for Js<T> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        Context::Js.write(out, |out| write!(out, "{}", &self.0))
+    }
+}
@@ -29,16 +29,18 @@
 //!   let template = "RsHtml";
 //!   let hello = v!(<p>Hello {template}</p>);
 //!
-//!   let mut out = String::with_capacity(hello.text_size());
+//!   let out = hello.render_to_string()?;
 //!
-//!   hello.render(&mut out)?;
-//!  
 //!   print!("{out}");
+//!   print!("{}", hello.display());
 //!
 //!   Ok(())
 //! }
 //! ```
 
+pub mod cache;
+pub mod escaper;
+pub use escaper::Escaper;
 /// Utility functions for use directly in RsHtml templates.
 ///
 /// Example template usage: `@time(&self.my_date)`, `@json(&self.data)`.
@@ -48,6 +50,18 @@ pub mod traits;
 
 mod escaping_writer;
 pub use escaping_writer::EscapingWriter;
+mod context;
+pub use context::{Context, Unquoted};
+mod markup;
+pub use markup::{Escaped, Markup, PreEscaped, SafeHtml, SafeHtmlCheck};
+
+mod error;
+pub use error::Error;
+mod io_writer;
+pub mod stream;
+pub use stream::flush;
+mod async_view;
+pub use async_view::{Async, AsyncView, render_async};
 
 /// The primary derive macro for enabling RsHtml templating on a struct.
 ///
@@ -61,6 +75,10 @@ pub use escaping_writer::EscapingWriter;
 /// This path can be customized using the `#[rshtml(path = "custom.rs.html")]` attribute
 /// on the struct.
 ///
+/// Expressions are escaped for html, unless another output format is chosen with
+/// `#[rshtml(escape = "xml")]` (`html`, `xml`, `text`, `json` or the path of an [`Escaper`])
+/// or by a template extension like `feed.rs.xml`, `mail.rs.txt` or `data.rs.json`.
+///
 /// Once derived, an instance of the struct will have a `render()` method to produce the HTML output.
 pub use rshtml_macro::RsHtml;
 
@@ -76,12 +94,50 @@ mod expr;
 pub use expr::{Block, Expr};
 
 mod exp;
-pub use exp::Exp;
+pub use exp::{Exp, ExpWith, ExpWithView};
+mod attr;
+pub use attr::{Attr, AttrSeparator, AttrValue, AttrView};
+mod attributes;
+pub use attributes::Attributes;
+mod js;
+pub use js::Js;
+mod css;
+pub use css::Css;
+mod view_display;
+pub use view_display::ViewDisplay;
 mod view_fn;
-pub use view_fn::ViewFn;
+pub use view_fn::{SharedView, ViewFn};
 mod text_size;
-pub use text_size::TextSize;
+pub use text_size::{AdaptiveSize, TextSize};
 mod view_iter;
-pub use view_iter::ViewIter;
+pub use view_iter::{ViewIter, ViewIterCloned};
+mod combinators;
+pub use combinators::{Join, Memo, Or, When, WrapIn};
 
 pub use rshtml_macro::v;
+
+/// Loads a `v!` template from the views folder, e.g. `v_file!("cards/list.html")`.
+///
+/// The file is parsed with the same syntax as `v!` and its `{expr}` holes are
+/// resolved against the variables at the call site.
+pub use rshtml_macro::v_file;
+
+/// Makes a function with typed props a `v!` component, e.g. `<Badge label={name}/>`.
+///
+/// A struct of the same name holds the props and implements [`traits::Component`]
+/// by calling the function with clones of them; a `children` parameter, typically
+/// `children: impl View`, receives the body of the tag.
+///
+/// ```
+/// use rshtml::{component, traits::View, v};
+///
+/// #[component]
+/// fn Badge(label: &str, count: usize, children: impl View) -> impl View {
+///     v!(<span class="badge">{label.to_owned()} {count}{children}</span>)
+/// }
+///
+/// let view = v!(<Badge label="new" count=3>!</Badge>);
+///
+/// assert_eq!(view.render_to_string().unwrap(), "<span class=\"badge\">new 3!</span>");
+/// ```
+pub use rshtml_macro::component;
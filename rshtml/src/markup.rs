@@ -0,0 +1,149 @@
+use crate::{
+    AttrValue, Escaper,
+    attr::{escape_attribute, write_attribute},
+    traits::{AttributeValue, Render, View},
+};
+use std::{
+    fmt::{self, Display},
+    ops::Deref,
+};
+
+/// Output that is already safe html, written as it is by both `v!` and templates
+/// as element content, attribute values still escape it.
+///
+/// Everything else is escaped for where it is written; a value implementing `SafeHtml`
+/// is trusted like `@#expr`, so only sanitizers and trusted sources should produce one.
+pub trait SafeHtml {
+    fn write_html(&self, out: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+impl<T: SafeHtml + ?Sized> SafeHtml for &T {
+    fn write_html(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        (*self).write_html(out)
+    }
+}
+
+/// A string trusted as safe html, e.g. `PreEscaped("<b>bold</b>")`.
+///
+/// ```
+/// use rshtml::{Markup, PreEscaped, traits::View, v};
+///
+/// let icon: Markup = PreEscaped("<svg></svg>".to_owned());
+/// let view = v!(<i>{icon}</i>);
+///
+/// assert_eq!(view.render_to_string().unwrap(), "<i><svg></svg></i>");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PreEscaped<T>(pub T);
+
+/// Owned safe html, returned by functions like `rshtml::functions::json_script`.
+pub type Markup = PreEscaped<String>;
+
+impl<T: AsRef<str>> PreEscaped<T> {
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<T> PreEscaped<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: AsRef<str>> SafeHtml for PreEscaped<T> {
+    fn write_html(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        out.write_str(self.as_str())
+    }
+}
+
+impl<T: AsRef<str>> View for PreEscaped<T> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_html(out)
+    }
+
+    fn text_size(&self) -> usize {
+        self.as_str().len()
+    }
+}
+
+impl<T: AsRef<str>> Render for PreEscaped<T> {
+    fn render(&self, out: &mut dyn fmt::Write, _e: &'static str) -> fmt::Result {
+        self.write_html(out)
+    }
+}
+
+impl<T: AsRef<str>> Render for &PreEscaped<T> {
+    fn render(&self, out: &mut dyn fmt::Write, _e: &'static str) -> fmt::Result {
+        self.write_html(out)
+    }
+}
+
+/// Renders `name="value"` with the value escaped like any other string,
+/// markup is only trusted as element content.
+impl<T: AsRef<str>> AttributeValue for PreEscaped<T> {
+    fn to_attribute(
+        &self,
+        name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        escape_attribute(name, &self.as_str(), escaper).map(Some)
+    }
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        write_attribute(name, &self.as_str(), out)
+    }
+}
+
+impl<T: AsRef<str>> Display for PreEscaped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for Markup {
+    fn from(html: String) -> Self {
+        Self(html)
+    }
+}
+
+/// Writes `SafeHtml` values as they are and escapes any other value with the escaper
+/// of the template, used by templates.
+#[doc(hidden)]
+pub struct SafeHtmlCheck<'a, T: ?Sized>(pub &'a T);
+
+impl<T: SafeHtml + ?Sized> SafeHtmlCheck<'_, T> {
+    #[inline(always)]
+    pub fn write(
+        &self,
+        out: &mut dyn fmt::Write,
+        _escaper: impl Escaper,
+        _render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> fmt::Result {
+        self.0.write_html(out)
+    }
+}
+
+impl<T: ?Sized> Deref for SafeHtmlCheck<'_, T> {
+    type Target = Escaped;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &Escaped
+    }
+}
+
+#[doc(hidden)]
+pub struct Escaped;
+
+impl Escaped {
+    #[inline(always)]
+    pub fn write(
+        &self,
+        out: &mut dyn fmt::Write,
+        escaper: impl Escaper,
+        render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    ) -> fmt::Result {
+        escaper.write(out, render)
+    }
+}
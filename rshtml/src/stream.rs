@@ -0,0 +1,242 @@
+//! Chunked rendering, so the start of a page can be sent while the rest is still rendering.
+//!
+//! Chunks are emitted once the buffered output reaches the chunk size, and at every
+//! [`flush()`] marker, e.g. `@flush` in a template or `{rshtml::flush()}` in `v!`.
+
+use crate::{Error, traits::View};
+use std::{
+    cell::Cell,
+    fmt, io,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, OnceLock, mpsc},
+    thread,
+};
+
+thread_local! {
+    // Whether a flush was requested, `None` outside of a chunked render.
+    static FLUSH: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+// Scopes the flush flag to one chunked render, restoring the flag of an outer one.
+struct FlushScope(Option<bool>);
+
+impl FlushScope {
+    fn enter() -> Self {
+        Self(FLUSH.with(|flush| flush.replace(Some(false))))
+    }
+}
+
+impl Drop for FlushScope {
+    fn drop(&mut self) {
+        FLUSH.with(|flush| flush.set(self.0));
+    }
+}
+
+/// A view that ends the current chunk when rendered by a chunked renderer,
+/// and renders nothing otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Flush;
+
+pub fn flush() -> Flush {
+    Flush
+}
+
+impl View for Flush {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        FLUSH.with(|flush| {
+            if flush.get().is_some() {
+                flush.set(Some(true));
+            }
+        });
+        // An empty write lets the chunk writer see the request right away.
+        out.write_str("")
+    }
+}
+
+struct ChunkWriter<'a> {
+    buffer: String,
+    chunk_size: usize,
+    on_chunk: &'a mut dyn FnMut(&str) -> io::Result<()>,
+    error: Option<io::Error>,
+}
+
+impl ChunkWriter<'_> {
+    fn emit(&mut self) -> fmt::Result {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        let result = (self.on_chunk)(&self.buffer);
+        self.buffer.clear();
+        result.map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+impl fmt::Write for ChunkWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if FLUSH.with(|flush| flush.replace(Some(false))) == Some(true) {
+            self.emit()?;
+        }
+
+        self.buffer.push_str(s);
+        if self.buffer.len() >= self.chunk_size {
+            self.emit()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `render` and passes its output to `on_chunk` in chunks of about `chunk_size` bytes.
+pub fn render_chunked(
+    chunk_size: usize,
+    render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    on_chunk: &mut dyn FnMut(&str) -> io::Result<()>,
+) -> Result<(), Error> {
+    let _scope = FlushScope::enter();
+
+    let mut writer = ChunkWriter {
+        buffer: String::with_capacity(chunk_size),
+        chunk_size,
+        on_chunk,
+        error: None,
+    };
+
+    let result = render(&mut writer).and_then(|()| writer.emit());
+
+    match writer.error {
+        Some(err) => Err(Error::Io(err)),
+        None => result.map_err(Error::Fmt),
+    }
+}
+
+/// An iterator over the chunks of a view rendered on a separate thread.
+///
+/// The views are rendered by a pool of one thread per cpu shared by all `Chunks`.
+/// A render stays at most `CHUNKS_AHEAD` chunks ahead of its consumer, so a slow
+/// consumer also holds its render thread. Dropping the iterator stops the render
+/// at its next chunk, and a panicking view ends the chunks with `Error::Panic`.
+pub struct Chunks {
+    receiver: mpsc::Receiver<Result<String, Error>>,
+}
+
+const CHUNKS_AHEAD: usize = 2;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// The render pool, its threads are started with the first `Chunks`.
+fn render_pool() -> &'static mpsc::Sender<Job> {
+    static POOL: OnceLock<mpsc::Sender<Job>> = OnceLock::new();
+
+    POOL.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = thread::available_parallelism().map_or(4, |n| n.get());
+
+        for i in 0..threads {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("rshtml-render-{i}"))
+                .spawn(move || {
+                    loop {
+                        let job = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => return,
+                        };
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => return,
+                        }
+                    }
+                })
+                .expect("failed to start an rshtml render thread");
+        }
+
+        sender
+    })
+}
+
+// Renders in chunks and passes them to `send`, ending with an error item if the
+// render fails or panics, so the consumer does not see a truncated output as complete.
+fn render_to_sender(
+    chunk_size: usize,
+    render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+    send: &mut dyn FnMut(Result<&str, Error>) -> io::Result<()>,
+) {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        render_chunked(chunk_size, render, &mut |chunk| send(Ok(chunk)))
+    }));
+
+    match result {
+        Ok(Ok(())) | Ok(Err(Error::Io(_))) => (),
+        Ok(Err(err)) => drop(send(Err(err))),
+        Err(_) => drop(send(Err(Error::Panic))),
+    }
+}
+
+impl Chunks {
+    pub fn new(
+        chunk_size: usize,
+        render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(CHUNKS_AHEAD);
+
+        let job: Job = Box::new(move || {
+            render_to_sender(chunk_size, render, &mut |chunk| {
+                sender
+                    .send(chunk.map(str::to_owned))
+                    .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+            });
+        });
+
+        let _ = render_pool().send(job);
+
+        Self { receiver }
+    }
+}
+
+impl Iterator for Chunks {
+    type Item = Result<String, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// A streaming axum response body, rendered on the blocking thread pool of tokio.
+#[cfg(feature = "axum")]
+pub fn body(
+    chunk_size: usize,
+    render: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result + Send + 'static,
+) -> axum_core::body::Body {
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use tokio::sync::mpsc;
+
+    struct ChunkStream(mpsc::Receiver<Result<bytes::Bytes, Error>>);
+
+    impl futures_core::Stream for ChunkStream {
+        type Item = Result<bytes::Bytes, Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.0.poll_recv(cx)
+        }
+    }
+
+    let (sender, receiver) = mpsc::channel(1);
+
+    tokio::task::spawn_blocking(move || {
+        render_to_sender(chunk_size, render, &mut |chunk| {
+            sender
+                .blocking_send(chunk.map(|chunk| bytes::Bytes::copy_from_slice(chunk.as_bytes())))
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+        });
+    });
+
+    axum_core::body::Body::from_stream(ChunkStream(receiver))
+}
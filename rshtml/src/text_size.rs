@@ -1,7 +1,14 @@
-use std::ops::Deref;
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use crate::traits::View;
 
+/// The estimated size of a value whose length is only known once it is rendered,
+/// e.g. a float or a type that implements only `Display`.
+pub(crate) const DYNAMIC_SIZE_HINT: usize = 16;
+
 pub struct TextSize<T>(pub T);
 
 impl<T: View> TextSize<T> {
@@ -10,10 +17,50 @@ impl<T: View> TextSize<T> {
     }
 }
 
+/// The fallback for `Display` values, which are not rendered ahead of time to be measured.
+#[doc(hidden)]
+pub struct DisplaySize;
+
+impl DisplaySize {
+    pub fn text_size(&self) -> usize {
+        DYNAMIC_SIZE_HINT
+    }
+}
+
 impl<T> Deref for TextSize<T> {
-    type Target = ();
+    type Target = DisplaySize;
 
     fn deref(&self) -> &Self::Target {
-        &()
+        &DisplaySize
+    }
+}
+
+/// Remembers the length of the last render to pre-size the next buffer,
+/// used by templates with `#[rshtml(adaptive_size)]`.
+///
+/// ```
+/// use rshtml::{AdaptiveSize, traits::View, v};
+///
+/// static SIZE: AdaptiveSize = AdaptiveSize::new(64);
+///
+/// let view = v!(<p>adaptive</p>);
+/// let mut out = String::with_capacity(SIZE.get());
+/// view.render(&mut out).unwrap();
+/// SIZE.record(out.len());
+/// ```
+#[derive(Debug)]
+pub struct AdaptiveSize(AtomicUsize);
+
+impl AdaptiveSize {
+    pub const fn new(initial: usize) -> Self {
+        Self(AtomicUsize::new(initial))
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn record(&self, len: usize) {
+        self.0.store(len, Ordering::Relaxed);
     }
 }
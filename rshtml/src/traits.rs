@@ -1,13 +1,75 @@
-use crate::{EscapingWriter, ViewIter};
+use crate::{
+    AttrValue, Error, Escaper, EscapingWriter, ViewDisplay, ViewIter, ViewIterCloned, async_view,
+    attr::{escape_attribute, write_attr_value, write_attribute, write_attribute_escaped},
+    combinators::{Join, Memo, Or, When, WrapIn},
+    io_writer::IoWriter,
+    stream::{self, Chunks},
+    text_size::DYNAMIC_SIZE_HINT,
+};
 use std::{
     borrow::Cow,
     cell::RefCell,
     fmt::{self, Write},
+    io,
+    rc::Rc,
+    sync::Arc,
 };
 
 pub trait RsHtml {
     fn fmt(&self, __f__: &mut dyn fmt::Write) -> fmt::Result;
     fn render(&self) -> Result<String, fmt::Error>;
+
+    /// The same as `render`, named like `View::render_to_string`.
+    fn render_to_string(&self) -> Result<String, fmt::Error> {
+        self.render()
+    }
+
+    /// An adapter implementing `fmt::Display`, e.g. `println!("{}", page.display())`.
+    fn display(&self) -> ViewDisplay<'_, Self>
+    where
+        Self: Sized,
+    {
+        ViewDisplay::new(self, Self::fmt)
+    }
+
+    fn render_to_vec(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.render()?.into_bytes())
+    }
+
+    /// Renders straight into `out`, a failing writer is reported as `Error::Io`.
+    fn render_to_io(&self, out: &mut dyn io::Write) -> Result<(), Error> {
+        IoWriter::new(out).write(|out| self.fmt(out))
+    }
+
+    #[cfg(feature = "bytes")]
+    fn render_to_bytes(&self, out: &mut bytes::BytesMut) -> Result<(), Error> {
+        self.fmt(out).map_err(Error::Fmt)
+    }
+
+    /// Passes the output to `on_chunk` in chunks of about `chunk_size` bytes and at every `@flush`.
+    fn render_chunked(
+        &self,
+        chunk_size: usize,
+        on_chunk: &mut dyn FnMut(&str) -> io::Result<()>,
+    ) -> Result<(), Error> {
+        stream::render_chunked(chunk_size, |out| self.fmt(out), on_chunk)
+    }
+
+    /// Renders on a thread of a shared render pool, yielding the chunks as they are ready.
+    fn into_chunks(self, chunk_size: usize) -> Chunks
+    where
+        Self: Sized + Send + 'static,
+    {
+        Chunks::new(chunk_size, move |out| self.fmt(out))
+    }
+
+    #[cfg(feature = "axum")]
+    fn into_body(self, chunk_size: usize) -> axum_core::body::Body
+    where
+        Self: Sized + Send + 'static,
+    {
+        stream::body(chunk_size, move |out| self.fmt(out))
+    }
 }
 
 pub trait Render {
@@ -20,6 +82,74 @@ pub trait View {
     fn text_size(&self) -> usize {
         0
     }
+
+    /// Renders with its text escaped by `escaper` instead of for html, used by
+    /// `v!(escape = .., ...)`. Views of markup, like a nested `v!`, render as they are
+    /// and containers such as `Option` or `Vec` pass the escaper on to their items.
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        let _ = escaper;
+        self.render(out)
+    }
+
+    /// Renders into a `String` allocated with `text_size` bytes.
+    fn render_to_string(&self) -> Result<String, fmt::Error> {
+        let mut out = String::with_capacity(self.text_size());
+        async_view::buffered(|| self.render(&mut out))?;
+        Ok(out)
+    }
+
+    /// An adapter implementing `fmt::Display`, so the view works in `format!`, `println!` or logging.
+    /// For a `&dyn View`, call it on a reference: `(&view).display()`.
+    fn display(&self) -> ViewDisplay<'_, Self>
+    where
+        Self: Sized,
+    {
+        ViewDisplay::new(self, Self::render)
+    }
+
+    /// Renders into a byte vector allocated with `text_size` bytes.
+    fn render_to_vec(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.render_to_string()?.into_bytes())
+    }
+
+    /// Renders straight into `out`, a failing writer is reported as `Error::Io`.
+    fn render_to_io(&self, out: &mut dyn io::Write) -> Result<(), Error> {
+        IoWriter::new(out).write(|out| self.render(out))
+    }
+
+    /// Appends the rendered view to `out`, reserving `text_size` bytes first.
+    #[cfg(feature = "bytes")]
+    fn render_to_bytes(&self, out: &mut bytes::BytesMut) -> Result<(), Error> {
+        out.reserve(self.text_size());
+        self.render(out).map_err(Error::Fmt)
+    }
+
+    /// Passes the output to `on_chunk` in chunks of about `chunk_size` bytes
+    /// and at every [`flush()`](crate::flush) marker.
+    fn render_chunked(
+        &self,
+        chunk_size: usize,
+        on_chunk: &mut dyn FnMut(&str) -> io::Result<()>,
+    ) -> Result<(), Error> {
+        stream::render_chunked(chunk_size, |out| self.render(out), on_chunk)
+    }
+
+    /// Renders on a thread of a shared render pool, yielding the chunks as they are ready.
+    fn into_chunks(self, chunk_size: usize) -> Chunks
+    where
+        Self: Sized + Send + 'static,
+    {
+        Chunks::new(chunk_size, move |out| self.render(out))
+    }
+
+    /// A streaming axum response body, e.g. `Response::new(page.into_body(8 * 1024))`.
+    #[cfg(feature = "axum")]
+    fn into_body(self, chunk_size: usize) -> axum_core::body::Body
+    where
+        Self: Sized + Send + 'static,
+    {
+        stream::body(chunk_size, move |out| self.render(out))
+    }
 }
 
 impl<T: View + ?Sized> View for &T {
@@ -27,6 +157,10 @@ impl<T: View + ?Sized> View for &T {
         (*self).render(out)
     }
 
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        (*self).render_escaped(escaper, out)
+    }
+
     fn text_size(&self) -> usize {
         (*self).text_size()
     }
@@ -39,6 +173,17 @@ impl<T: View> View for [T] {
         }
         Ok(())
     }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        for item in self {
+            item.render_escaped(escaper, out)?;
+        }
+        Ok(())
+    }
+
+    fn text_size(&self) -> usize {
+        self.iter().map(View::text_size).sum()
+    }
 }
 
 impl<T: View> View for Vec<T> {
@@ -48,49 +193,392 @@ impl<T: View> View for Vec<T> {
         }
         Ok(())
     }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.as_slice().render_escaped(escaper, out)
+    }
+
+    fn text_size(&self) -> usize {
+        self.as_slice().text_size()
+    }
 }
 
-impl<T: View + ?Sized> View for Box<T> {
+impl<T: View, const N: usize> View for [T; N] {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.as_slice().render(out)
+    }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.as_slice().render_escaped(escaper, out)
+    }
+
+    fn text_size(&self) -> usize {
+        self.iter().map(View::text_size).sum()
+    }
+}
+
+macro_rules! impl_view_for_pointer {
+    ($($t:ident),*) => {
+        $(
+            impl<T: View + ?Sized> View for $t<T> {
+                fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+                    (**self).render(out)
+                }
+
+                fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+                    (**self).render_escaped(escaper, out)
+                }
+
+                fn text_size(&self) -> usize {
+                    (**self).text_size()
+                }
+            }
+        )*
+    };
+}
+
+impl_view_for_pointer!(Box, Rc, Arc);
+
+impl<T: View + ToOwned + ?Sized> View for Cow<'_, T> {
     fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
         (**self).render(out)
     }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        (**self).render_escaped(escaper, out)
+    }
+
+    fn text_size(&self) -> usize {
+        (**self).text_size()
+    }
 }
 
-impl View for () {
-    fn render(&self, _out: &mut dyn fmt::Write) -> fmt::Result {
-        Ok(())
+/// Renders nothing for `None`.
+impl<T: View> View for Option<T> {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Some(view) => view.render(out),
+            None => Ok(()),
+        }
+    }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Some(view) => view.render_escaped(escaper, out),
+            None => Ok(()),
+        }
+    }
+
+    fn text_size(&self) -> usize {
+        self.as_ref().map_or(0, View::text_size)
     }
 }
 
-impl<'a> View for Cow<'a, str> {
+/// Renders the error view for `Err`.
+impl<T: View, E: View> View for Result<T, E> {
     fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
-        (**self).render(out)
+        match self {
+            Ok(view) => view.render(out),
+            Err(err) => err.render(out),
+        }
     }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Ok(view) => view.render_escaped(escaper, out),
+            Err(err) => err.render_escaped(escaper, out),
+        }
+    }
+
     fn text_size(&self) -> usize {
-        self.len()
+        match self {
+            Ok(view) => view.text_size(),
+            Err(err) => err.text_size(),
+        }
+    }
+}
+
+impl View for () {
+    fn render(&self, _out: &mut dyn fmt::Write) -> fmt::Result {
+        Ok(())
     }
 }
 
+macro_rules! impl_view_for_tuple {
+    ($(($($t:ident $i:tt),+)),*) => {
+        $(
+            impl<$($t: View),+> View for ($($t,)+) {
+                fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+                    $(self.$i.render(out)?;)+
+                    Ok(())
+                }
+
+                fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+                    $(self.$i.render_escaped(escaper, out)?;)+
+                    Ok(())
+                }
+
+                fn text_size(&self) -> usize {
+                    0 $(+ self.$i.text_size())+
+                }
+            }
+        )*
+    };
+}
+
+impl_view_for_tuple!(
+    (A 0),
+    (A 0, B 1),
+    (A 0, B 1, C 2),
+    (A 0, B 1, C 2, D 3),
+    (A 0, B 1, C 2, D 3, E 4),
+    (A 0, B 1, C 2, D 3, E 4, F 5),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10),
+    (A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11)
+);
+
 impl<'a> View for fmt::Arguments<'a> {
     fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
         write!(&mut EscapingWriter { inner: out }, "{}", self)
     }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        Escaper::write(&escaper, out, |out| write!(out, "{}", self))
+    }
+
+    fn text_size(&self) -> usize {
+        self.as_str().map_or(DYNAMIC_SIZE_HINT, str::len)
+    }
 }
 
 macro_rules! impl_view_for_display {
-    ($($t:ty),*) => {
+    ($($t:ty => |$value:ident| $size:expr),*) => {
         $(
             impl View for $t {
                 fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
                     write!(&mut EscapingWriter { inner: out }, "{}", self)
                 }
+
+                fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+                    Escaper::write(&escaper, out, |out| write!(out, "{}", self))
+                }
+
+                fn text_size(&self) -> usize {
+                    let $value = *self;
+                    $size
+                }
             }
         )*
     };
 }
 
+macro_rules! impl_view_for_integer {
+    ($($t:ty),*) => {
+        impl_view_for_display!($($t => |value| digits(value.unsigned_abs() as u128) + usize::from(value < 0)),*);
+    };
+    (unsigned $($t:ty),*) => {
+        impl_view_for_display!($($t => |value| digits(value as u128)),*);
+    };
+}
+
+fn digits(value: u128) -> usize {
+    value
+        .checked_ilog10()
+        .map_or(1, |digits| digits as usize + 1)
+}
+
+impl View for str {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        EscapingWriter { inner: out }.write_str(self)
+    }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        escaper.escape_str(self, out)
+    }
+
+    fn text_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl View for String {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        self.as_str().render(out)
+    }
+
+    fn render_escaped(&self, escaper: &dyn Escaper, out: &mut dyn fmt::Write) -> fmt::Result {
+        escaper.escape_str(self, out)
+    }
+
+    fn text_size(&self) -> usize {
+        self.len()
+    }
+}
+
 impl_view_for_display!(
-    String, str, char, bool, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32,
+    char => |value| value.len_utf8(),
+    bool => |value| if value { 4 } else { 5 },
+    f32 => |_value| DYNAMIC_SIZE_HINT,
+    f64 => |_value| DYNAMIC_SIZE_HINT
+);
+impl_view_for_integer!(i8, i16, i32, i64, i128, isize);
+impl_view_for_integer!(unsigned u8, u16, u32, u64, u128, usize);
+
+/// A component used as a tag in `v!`, e.g. `<Card title={title}>children</Card>`.
+///
+/// The tag attributes become the fields of the struct and the tag body is passed as `children`.
+/// For a function taking the props, [`#[component]`](crate::component) writes the struct and this impl.
+pub trait Component {
+    fn render(&self, children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+/// A value of a `v!` attribute expression, e.g. `disabled={is_disabled}`.
+///
+/// `name` is the bare attribute name and the caller writes the whitespace in front of it,
+/// so an implementation can render `name="value"`, only `name`, or drop the attribute
+/// entirely by writing nothing.
+pub trait AttributeValue {
+    /// The attribute with its value escaped for `name`, or by `escaper` for
+    /// `v!(escape = .., ...)`, `None` leaves it out. [`Attributes`](crate::Attributes) stores it.
+    fn to_attribute(
+        &self,
+        name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error>;
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        write_attr_value(name, self.to_attribute(name, None)?.as_ref(), out)
+    }
+
+    /// Renders the attribute with its value escaped by `escaper` instead of for html,
+    /// used by `v!(escape = .., ...)`.
+    fn render_attribute_escaped(
+        &self,
+        name: &str,
+        escaper: &dyn Escaper,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        write_attr_value(name, self.to_attribute(name, Some(escaper))?.as_ref(), out)
+    }
+}
+
+impl<T: AttributeValue + ?Sized> AttributeValue for &T {
+    fn to_attribute(
+        &self,
+        name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        (*self).to_attribute(name, escaper)
+    }
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        (*self).render_attribute(name, out)
+    }
+
+    fn render_attribute_escaped(
+        &self,
+        name: &str,
+        escaper: &dyn Escaper,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        (*self).render_attribute_escaped(name, escaper, out)
+    }
+}
+
+impl AttributeValue for bool {
+    fn to_attribute(
+        &self,
+        _name: &str,
+        _escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        Ok(self.then_some(AttrValue::Bare))
+    }
+}
+
+impl<T: AttributeValue> AttributeValue for Option<T> {
+    fn to_attribute(
+        &self,
+        name: &str,
+        escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        match self {
+            Some(value) => value.to_attribute(name, escaper),
+            None => Ok(None),
+        }
+    }
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            Some(value) => value.render_attribute(name, out),
+            None => Ok(()),
+        }
+    }
+
+    fn render_attribute_escaped(
+        &self,
+        name: &str,
+        escaper: &dyn Escaper,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        match self {
+            Some(value) => value.render_attribute_escaped(name, escaper, out),
+            None => Ok(()),
+        }
+    }
+}
+
+macro_rules! impl_attribute_value_for_display {
+    ($($t:ty),*) => {
+        $(
+            impl AttributeValue for $t {
+                fn to_attribute(
+                    &self,
+                    name: &str,
+                    escaper: Option<&dyn Escaper>,
+                ) -> Result<Option<AttrValue>, fmt::Error> {
+                    escape_attribute(name, &self, escaper).map(Some)
+                }
+
+                fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+                    write_attribute(name, &self, out)
+                }
+
+                fn render_attribute_escaped(
+                    &self,
+                    name: &str,
+                    escaper: &dyn Escaper,
+                    out: &mut dyn fmt::Write,
+                ) -> fmt::Result {
+                    write_attribute_escaped(name, &self, escaper, out)
+                }
+            }
+        )*
+    };
+}
+
+impl_attribute_value_for_display!(
+    String,
+    str,
+    Cow<'_, str>,
+    fmt::Arguments<'_>,
+    char,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
     f64
 );
 
@@ -101,6 +589,66 @@ pub trait IntoViewIter: IntoIterator + Sized {
     {
         ViewIter(RefCell::new(Some(self.into_iter())))
     }
+
+    /// A view that renders any number of times, for iterators that are `Clone`
+    /// such as `collection.iter()` or `(&collection).into_iter().map(..)`.
+    fn view_iter_cloned(self) -> ViewIterCloned<Self::IntoIter>
+    where
+        Self::Item: View,
+        Self::IntoIter: Clone,
+    {
+        ViewIterCloned(self.into_iter())
+    }
 }
 
 impl<T: IntoIterator> IntoViewIter for T {}
+
+/// Renders the views of an iterator with a separator between them.
+pub trait ViewIterExt: Iterator + Sized {
+    /// E.g. `tags.iter().map(|tag| v!(<b>{tag}</b>)).join_views(", ")`, consumed by the first render.
+    fn join_views<S: View>(self, separator: S) -> Join<Self, S>
+    where
+        Self::Item: View,
+    {
+        Join::new(self, separator)
+    }
+}
+
+impl<I: Iterator> ViewIterExt for I {}
+
+/// Combinators for composing views without writing a `v!` or an `impl View` for each pattern.
+pub trait ViewExt: View + Sized {
+    /// Renders the view only when `condition` holds.
+    fn when(self, condition: bool) -> When<Self> {
+        When(self, condition)
+    }
+
+    /// Renders `other` when this view renders nothing, e.g. `list.when(!empty).or_else_view("No items")`.
+    fn or_else_view<O: View>(self, other: O) -> Or<Self, O> {
+        Or(self, other)
+    }
+
+    /// Renders the view inside `<tag attributes>..</tag>`, `attributes` is e.g. an `Attributes` or `()`.
+    fn wrap_in<A: View>(self, tag: &'static str, attributes: A) -> WrapIn<Self, A> {
+        WrapIn {
+            tag,
+            attributes,
+            view: self,
+        }
+    }
+
+    fn prefix<P: View>(self, prefix: P) -> (P, Self) {
+        (prefix, self)
+    }
+
+    fn suffix<S: View>(self, suffix: S) -> (Self, S) {
+        (self, suffix)
+    }
+
+    /// Renders the view once and writes the cached output on every later render.
+    fn memo(self) -> Memo<Self> {
+        Memo::new(self)
+    }
+}
+
+impl<T: View> ViewExt for T {}
@@ -0,0 +1,19 @@
+use std::fmt;
+
+/// Formats a view or a template with `fmt::Display`, created with `View::display` or `RsHtml::display`.
+pub struct ViewDisplay<'a, T: ?Sized> {
+    value: &'a T,
+    render: fn(&T, &mut dyn fmt::Write) -> fmt::Result,
+}
+
+impl<'a, T: ?Sized> ViewDisplay<'a, T> {
+    pub(crate) fn new(value: &'a T, render: fn(&T, &mut dyn fmt::Write) -> fmt::Result) -> Self {
+        Self { value, render }
+    }
+}
+
+impl<T: ?Sized> fmt::Display for ViewDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (self.render)(self.value, f)
+    }
+}
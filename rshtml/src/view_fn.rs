@@ -1,5 +1,9 @@
-use crate::traits::View;
-use std::{fmt, ops::Deref};
+use crate::{
+    AttrValue, Escaper,
+    escaping_writer::QuoteEscapingWriter,
+    traits::{AttributeValue, View},
+};
+use std::{fmt, ops::Deref, sync::Arc};
 
 pub struct ViewFn<T>(pub T, usize);
 
@@ -22,6 +26,28 @@ where
     }
 }
 
+impl<T> AttributeValue for ViewFn<T>
+where
+    T: Fn(&mut dyn fmt::Write) -> fmt::Result,
+{
+    // A nested `v!` keeps its own escaper.
+    fn to_attribute(
+        &self,
+        _name: &str,
+        _escaper: Option<&dyn Escaper>,
+    ) -> Result<Option<AttrValue>, fmt::Error> {
+        let mut value = String::new();
+        (self.0)(&mut QuoteEscapingWriter { inner: &mut value })?;
+        Ok(Some(AttrValue::Value(value)))
+    }
+
+    fn render_attribute(&self, name: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        write!(out, "{name}=\"")?;
+        (self.0)(&mut QuoteEscapingWriter { inner: out })?;
+        out.write_char('"')
+    }
+}
+
 impl<'a, T> ViewFn<T>
 where
     T: Fn(&mut dyn fmt::Write) -> fmt::Result + 'a,
@@ -31,6 +57,24 @@ where
     }
 }
 
+impl<'a, T> ViewFn<T>
+where
+    T: Fn(&mut dyn fmt::Write) -> fmt::Result + Send + Sync + 'a,
+{
+    /// A boxed view that can be held across an `.await` or sent to another task.
+    pub fn boxed_send(self) -> Box<dyn View + Send + Sync + 'a> {
+        Box::new(self)
+    }
+
+    /// A view shared between tasks, a [`SharedView`] when nothing is borrowed.
+    pub fn arc(self) -> Arc<dyn View + Send + Sync + 'a> {
+        Arc::new(self)
+    }
+}
+
+/// A view that can be cloned cheaply and shared between threads, e.g. created with `v!(..).arc()`.
+pub type SharedView = Arc<dyn View + Send + Sync>;
+
 impl<T> Deref for ViewFn<T> {
     type Target = T;
 
@@ -1,6 +1,10 @@
-use crate::traits::View;
+use crate::{text_size::DYNAMIC_SIZE_HINT, traits::View};
 use std::{cell::RefCell, fmt};
 
+/// Renders the views of an iterator, created with `IntoViewIter::view_iter`.
+///
+/// The iterator is consumed by the first render, rendering it again returns `fmt::Error`.
+/// Use [`ViewIterCloned`] for a view that can be rendered any number of times.
 pub struct ViewIter<I>(pub RefCell<Option<I>>);
 
 impl<I, V> View for ViewIter<I>
@@ -9,11 +13,44 @@ where
     V: View,
 {
     fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
-        if let Some(iter) = self.0.borrow_mut().take() {
-            for item in iter {
-                item.render(out)?;
-            }
+        let iter = self.0.borrow_mut().take().ok_or(fmt::Error)?;
+        for item in iter {
+            item.render(out)?;
         }
         Ok(())
     }
+
+    /// Estimated from the iterator's `size_hint`, as the items are only known once rendered.
+    fn text_size(&self) -> usize {
+        self.0.borrow().as_ref().map_or(0, size_hint)
+    }
+}
+
+/// Renders the views of a `Clone` iterator, created with `IntoViewIter::view_iter_cloned`.
+///
+/// Every render iterates over a clone, so it renders any number of times and is
+/// `Send + Sync` whenever the iterator is, e.g. `users.iter().map(user_card).view_iter_cloned()`.
+#[derive(Debug, Clone)]
+pub struct ViewIterCloned<I>(pub I);
+
+impl<I, V> View for ViewIterCloned<I>
+where
+    I: Iterator<Item = V> + Clone,
+    V: View,
+{
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        for item in self.0.clone() {
+            item.render(out)?;
+        }
+        Ok(())
+    }
+
+    fn text_size(&self) -> usize {
+        size_hint(&self.0)
+    }
+}
+
+pub(crate) fn size_hint(iter: &impl Iterator) -> usize {
+    let (lower, upper) = iter.size_hint();
+    upper.unwrap_or(lower).saturating_mul(DYNAMIC_SIZE_HINT)
 }
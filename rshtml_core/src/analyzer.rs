@@ -72,11 +72,13 @@ impl Analyzer {
             }
             Node::ChildContent => ChildContentAnalyzer::analyze(self),
             Node::Raw(_) => (),
+            Node::CacheBlock(_, body, _) => body.iter().for_each(|node| self.analyze(node)),
             Node::UseDirective(name, path, component, position) => {
                 UseDirectiveAnalyzer::analyze(self, name, path, component, position)
             }
             Node::ContinueDirective => (),
             Node::BreakDirective => (),
+            Node::FlushDirective => (),
         }
     }
 
@@ -1,5 +1,7 @@
+mod cache_block;
 mod component;
 mod expr;
+mod html_context;
 mod match_expr;
 mod raw;
 mod rust_block;
@@ -12,9 +14,10 @@ mod use_directive;
 use crate::{
     Node,
     compiler::{
-        component::ComponentCompiler, expr::ExprCompiler, match_expr::MatchExprCompiler,
-        raw::RawCompiler, rust_block::RustBlockCompiler, rust_expr::RustExprCompiler,
-        template::TemplateCompiler, template_params::TemplateParamsCompiler, text::TextCompiler,
+        cache_block::CacheBlockCompiler, component::ComponentCompiler, expr::ExprCompiler,
+        html_context::HtmlContext, match_expr::MatchExprCompiler, raw::RawCompiler,
+        rust_block::RustBlockCompiler, rust_expr::RustExprCompiler, template::TemplateCompiler,
+        template_params::TemplateParamsCompiler, text::TextCompiler,
         use_directive::UseDirectiveCompiler,
     },
     diagnostic::Diagnostic,
@@ -31,6 +34,8 @@ pub struct Compiler {
     struct_generics: Generics,
     components: HashMap<PathBuf, Component>,
     pub text_size: usize,
+    html_context: HtmlContext,
+    escaper: Option<TokenStream>, // None for html, escaped for the html context
     pub files: Vec<(PathBuf, Position)>,
     is_root: bool,
     component_path: PathBuf,
@@ -38,12 +43,19 @@ pub struct Compiler {
 }
 
 impl Compiler {
-    pub fn new(struct_name: Ident, struct_generics: Generics, diagnostic: Diagnostic) -> Self {
+    pub fn new(
+        struct_name: Ident,
+        struct_generics: Generics,
+        diagnostic: Diagnostic,
+        escaper: Option<TokenStream>,
+    ) -> Self {
         Compiler {
             struct_name,
             struct_generics,
             components: HashMap::new(),
             text_size: 0,
+            html_context: HtmlContext::default(),
+            escaper,
             files: Vec::new(),
             is_root: false,
             component_path: PathBuf::new(),
@@ -75,11 +87,17 @@ impl Compiler {
             }
             Node::ChildContent => Ok(quote! {child_content(__f__)?;}),
             Node::Raw(body) => RawCompiler::compile(self, body),
+            Node::CacheBlock(key, body, position) => {
+                CacheBlockCompiler::compile(self, key, body, position)
+            }
             Node::UseDirective(name, path, component, position) => {
                 UseDirectiveCompiler::compile(self, name, path, *component, position)
             }
             Node::ContinueDirective => Ok(quote! {continue;}),
             Node::BreakDirective => Ok(quote! {break;}),
+            Node::FlushDirective => {
+                Ok(quote! {::rshtml::traits::View::render(&::rshtml::flush(), __f__)?;})
+            }
         }
     }
 
@@ -0,0 +1,95 @@
+use crate::Node;
+use crate::compiler::Compiler;
+use crate::position::Position;
+use anyhow::{Result, anyhow};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Expr, Token, parse::Parser, punctuated::Punctuated};
+
+pub struct CacheBlockCompiler;
+
+impl CacheBlockCompiler {
+    pub fn compile(
+        compiler: &mut Compiler,
+        key: String,
+        body: Vec<Node>,
+        position: Position,
+    ) -> Result<TokenStream> {
+        let mut body_ts = TokenStream::new();
+        for node in body {
+            let ts = compiler.compile(node)?;
+            body_ts.extend(quote! {#ts});
+        }
+
+        let CacheArgs { key, store, ttl } = CacheArgs::parse(&key)?;
+
+        // Keys are namespaced by the template, so the same key in two templates does not collide.
+        let namespace = compiler.component_path.to_string_lossy().into_owned();
+        let store = match store {
+            Some(store) => quote! { &*(#store) },
+            None => quote! { &*::rshtml::cache::default_store() },
+        };
+        let ttl = match ttl {
+            Some(ttl) => quote! { Some(#ttl) },
+            None => quote! { None },
+        };
+
+        let ts = quote! {
+            ::rshtml::cache::render_cached(
+                #store,
+                &::std::format!("{}:{}", #namespace, ::std::convert::AsRef::<str>::as_ref(&#key)),
+                #ttl,
+                __f__,
+                |__f__: &mut dyn ::std::fmt::Write| -> ::std::fmt::Result {
+                    #body_ts
+                    Ok(())
+                },
+            )?;
+        };
+
+        Ok(compiler.with_info(ts, position, None))
+    }
+}
+
+// (key, store = .., ttl = ..)
+struct CacheArgs {
+    key: Expr,
+    store: Option<Expr>,
+    ttl: Option<Expr>,
+}
+
+impl CacheArgs {
+    fn parse(args: &str) -> Result<Self> {
+        let args = args
+            .strip_prefix('(')
+            .and_then(|args| args.strip_suffix(')'))
+            .unwrap_or(args);
+        let mut args = Punctuated::<Expr, Token![,]>::parse_terminated
+            .parse_str(args)
+            .map_err(|err| anyhow!("Parse Error in @cache: {}", err))?
+            .into_iter();
+
+        let key = match args.next() {
+            Some(Expr::Assign(_)) | None => return Err(anyhow!("@cache needs a key first")),
+            Some(key) => key,
+        };
+
+        let (mut store, mut ttl) = (None, None);
+        for arg in args {
+            let Expr::Assign(assign) = arg else {
+                return Err(anyhow!("@cache takes a key, `store = ..` and `ttl = ..`"));
+            };
+
+            let option = match &*assign.left {
+                Expr::Path(path) if path.path.is_ident("store") => &mut store,
+                Expr::Path(path) if path.path.is_ident("ttl") => &mut ttl,
+                _ => return Err(anyhow!("@cache only takes `store = ..` and `ttl = ..`")),
+            };
+            if option.replace(*assign.right).is_some() {
+                return Err(anyhow!("@cache options can only be given once"));
+            }
+        }
+
+        Ok(Self { key, store, ttl })
+    }
+}
@@ -44,7 +44,21 @@ impl<'a> ExprCompiler<'a> {
                 "this expression does not implement the Display trait.",
                 expr.len(),
             );
-            Self::escape_or_raw(quote!(#expression), is_escaped, &message)
+            let (context, trusts_markup) = match &compiler.escaper {
+                Some(escaper) => (escaper.clone(), true),
+                None => (
+                    compiler.html_context.escape_context(),
+                    !compiler.html_context.in_attribute(),
+                ),
+            };
+            compiler.html_context.after_expr();
+            Self::escape_or_raw(
+                quote!(#expression),
+                is_escaped,
+                context,
+                trusts_markup,
+                &message,
+            )
         };
 
         let expr_ts = compiler.with_info(expr_ts, position, None);
@@ -52,9 +66,30 @@ impl<'a> ExprCompiler<'a> {
         Ok(expr_ts)
     }
 
-    fn escape_or_raw(expr_ts: TokenStream, is_escaped: bool, message: &str) -> TokenStream {
-        if is_escaped {
-            quote! { ::rshtml::Expr(&(#expr_ts)).render(&mut ::rshtml::EscapingWriter { inner: __f__ }, #message)?; }
+    // Escaped expressions are written with the escaper of the template, or for the html text,
+    // attribute, url, script or style they are in, unless their type is `SafeHtml`.
+    // Attribute values escape `SafeHtml` as well, markup cannot be trusted to keep to the quotes.
+    fn escape_or_raw(
+        expr_ts: TokenStream,
+        is_escaped: bool,
+        context: TokenStream,
+        trusts_markup: bool,
+        message: &str,
+    ) -> TokenStream {
+        if is_escaped && !trusts_markup {
+            quote! {
+                ::rshtml::Escaper::write(&#context, __f__, |__f__| {
+                    ::rshtml::Expr(&(#expr_ts)).render(__f__, #message)
+                })?;
+            }
+        } else if is_escaped {
+            quote! {
+                match &(#expr_ts) {
+                    __value__ => ::rshtml::SafeHtmlCheck(__value__).write(__f__, #context, |__f__| {
+                        ::rshtml::Expr(__value__).render(__f__, #message)
+                    })?,
+                }
+            }
         } else {
             quote! { ::rshtml::Expr(&(#expr_ts)).render(__f__, #message)?; }
         }
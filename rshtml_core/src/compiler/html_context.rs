@@ -0,0 +1,185 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Follows the html of the template text to know where the next expression is written.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HtmlContext {
+    state: State,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+enum State {
+    #[default]
+    Text,
+    Comment,
+    TagName(String),
+    EndTag,
+    Tag(String),
+    AttributeName(String, String),        // tag, attribute
+    BeforeAttributeValue(String, String), // tag, attribute, after `=`
+    AttributeValue(AttributeValue),
+    RawText(String), // body of a <script> or <style>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AttributeValue {
+    tag: String,
+    name: String,
+    quote: Option<char>,
+    at_start: bool, // nothing of the value is written yet
+}
+
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+impl HtmlContext {
+    pub fn scan(&mut self, text: &str) {
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            self.state = match std::mem::take(&mut self.state) {
+                State::Text => match c {
+                    '<' if text[i..].starts_with("<!--") => State::Comment,
+                    '<' => match chars.peek() {
+                        Some((_, '/')) => State::EndTag,
+                        Some((_, c)) if c.is_ascii_alphabetic() => State::TagName(String::new()),
+                        _ => State::Text,
+                    },
+                    _ => State::Text,
+                },
+                State::Comment if c == '>' && text[..i].ends_with("--") => State::Text,
+                State::Comment => State::Comment,
+                State::EndTag if c == '>' => State::Text,
+                State::EndTag => State::EndTag,
+                State::TagName(mut name) => match c {
+                    '>' => Self::open(name),
+                    c if c.is_whitespace() || c == '/' => State::Tag(name),
+                    c => {
+                        name.push(c.to_ascii_lowercase());
+                        State::TagName(name)
+                    }
+                },
+                State::Tag(tag) => match c {
+                    '>' => Self::open(tag),
+                    c if c.is_whitespace() || c == '/' => State::Tag(tag),
+                    c => State::AttributeName(tag, c.to_string()),
+                },
+                State::AttributeName(tag, mut name) => match c {
+                    '>' => Self::open(tag),
+                    '=' => State::BeforeAttributeValue(tag, name),
+                    c if c.is_whitespace() || c == '/' => State::Tag(tag),
+                    c => {
+                        name.push(c);
+                        State::AttributeName(tag, name)
+                    }
+                },
+                State::BeforeAttributeValue(tag, name) => match c {
+                    '>' => Self::open(tag),
+                    c if c.is_whitespace() => State::BeforeAttributeValue(tag, name),
+                    '"' | '\'' => State::AttributeValue(AttributeValue {
+                        tag,
+                        name,
+                        quote: Some(c),
+                        at_start: true,
+                    }),
+                    _ => State::AttributeValue(AttributeValue {
+                        tag,
+                        name,
+                        quote: None,
+                        at_start: false,
+                    }),
+                },
+                State::AttributeValue(mut value) => match (value.quote, c) {
+                    (Some(quote), c) if c == quote => State::Tag(value.tag),
+                    (None, '>') => Self::open(value.tag),
+                    (None, c) if c.is_whitespace() => State::Tag(value.tag),
+                    _ => {
+                        value.at_start = false;
+                        State::AttributeValue(value)
+                    }
+                },
+                State::RawText(tag) => {
+                    let end = text[i..].get(..tag.len() + 2);
+                    if end.is_some_and(|end| end.eq_ignore_ascii_case(&format!("</{tag}"))) {
+                        State::EndTag
+                    } else {
+                        State::RawText(tag)
+                    }
+                }
+            };
+        }
+    }
+
+    /// The `rshtml::Context` of an expression written at the current position.
+    pub fn escape_context(&self) -> TokenStream {
+        match &self.state {
+            State::AttributeValue(AttributeValue {
+                name,
+                quote: Some(_),
+                at_start,
+                ..
+            }) => quote! { ::rshtml::Context::attribute(#name, #at_start) },
+            State::AttributeValue(AttributeValue { name, at_start, .. }) => {
+                quote! { ::rshtml::Unquoted(::rshtml::Context::attribute(#name, #at_start)) }
+            }
+            State::BeforeAttributeValue(_, name) => {
+                quote! { ::rshtml::Unquoted(::rshtml::Context::attribute(#name, true)) }
+            }
+            State::RawText(tag) if tag == "style" => quote! { ::rshtml::Context::Css },
+            State::RawText(_) => quote! { ::rshtml::Context::Js },
+            _ => quote! { ::rshtml::Context::Text },
+        }
+    }
+
+    /// Whether the next expression is written in an attribute value.
+    pub fn in_attribute(&self) -> bool {
+        matches!(
+            self.state,
+            State::AttributeValue(_) | State::BeforeAttributeValue(..)
+        )
+    }
+
+    /// Moves past an expression, which is now part of any attribute value it is in.
+    pub fn after_expr(&mut self) {
+        self.state = match std::mem::take(&mut self.state) {
+            State::BeforeAttributeValue(tag, name) => State::AttributeValue(AttributeValue {
+                tag,
+                name,
+                quote: None,
+                at_start: false,
+            }),
+            State::AttributeValue(mut value) => {
+                value.at_start = false;
+                State::AttributeValue(value)
+            }
+            state => state,
+        };
+    }
+
+    /// The context after one of the branches of an `@if`, `@match` or loop ran.
+    ///
+    /// A value is at the start of an attribute when it is in any branch, and a branch
+    /// left in a tag, attribute or script wins over one back in the text, whose escaping
+    /// would not be enough there.
+    pub fn join(self, other: Self) -> Self {
+        let state = match (self.state, other.state) {
+            (State::AttributeValue(mut value), State::AttributeValue(other))
+                if (&value.tag, &value.name, value.quote)
+                    == (&other.tag, &other.name, other.quote) =>
+            {
+                value.at_start |= other.at_start;
+                State::AttributeValue(value)
+            }
+            (State::Text, state) | (state, _) => state,
+        };
+
+        Self { state }
+    }
+
+    fn open(tag: String) -> State {
+        if RAW_TEXT_ELEMENTS.contains(&tag.as_str()) {
+            State::RawText(tag)
+        } else {
+            State::Text
+        }
+    }
+}
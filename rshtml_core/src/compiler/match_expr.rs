@@ -1,5 +1,5 @@
 use crate::Node;
-use crate::compiler::Compiler;
+use crate::compiler::{Compiler, html_context::HtmlContext};
 use crate::position::Position;
 use anyhow::{Result, anyhow};
 use proc_macro2::TokenStream;
@@ -17,7 +17,11 @@ impl MatchExprCompiler {
     ) -> Result<TokenStream> {
         let mut arms_ts = TokenStream::new();
 
+        let start = compiler.html_context.clone();
+        let mut end: Option<HtmlContext> = None;
+
         for (arm_name, _, arm_nodes) in arms {
+            compiler.html_context = start.clone();
             let mut token_stream = TokenStream::new();
             for node in arm_nodes {
                 let ts = compiler.compile(node)?;
@@ -30,8 +34,16 @@ impl MatchExprCompiler {
             };
 
             arms_ts.extend(arm_ts);
+
+            let arm = std::mem::take(&mut compiler.html_context);
+            end = Some(match end {
+                Some(end) => end.join(arm),
+                None => arm,
+            });
         }
 
+        compiler.html_context = end.unwrap_or(start);
+
         let name_head =
             TokenStream::from_str(&head).map_err(|err| anyhow!("Lex Error: {}", err))?;
 
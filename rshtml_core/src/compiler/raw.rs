@@ -9,6 +9,7 @@ pub struct RawCompiler;
 impl RawCompiler {
     pub fn compile(compiler: &mut Compiler, body: String) -> Result<TokenStream> {
         compiler.text_size.add_assign(body.len());
+        compiler.html_context.scan(&body);
         Ok(quote! { write!(__f__, "{}", #body)?; })
     }
 }
@@ -16,7 +16,16 @@ impl RustExprCompiler {
     ) -> Result<TokenStream> {
         let mut ts = TokenStream::new();
 
+        // Every branch starts from the html context before the `@if`, and without
+        // an `else` or for a loop, none of them may run.
+        let start = compiler.html_context.clone();
+        let mut end = match exprs.last() {
+            Some((head, _, _)) if head == "else" => None,
+            _ => Some(start.clone()),
+        };
+
         for (expr, _, inner_nodes) in exprs {
+            compiler.html_context = start.clone();
             let mut inner_ts = TokenStream::new();
             for inner_node in inner_nodes {
                 let its = compiler.compile(inner_node)?;
@@ -27,8 +36,16 @@ impl RustExprCompiler {
                 TokenStream::from_str(&expr).map_err(|err| anyhow!("Lex Error: {}", err))?;
 
             ts.extend(quote! { #expr_code { #inner_ts } });
+
+            let branch = std::mem::take(&mut compiler.html_context);
+            end = Some(match end {
+                Some(end) => end.join(branch),
+                None => branch,
+            });
         }
 
+        compiler.html_context = end.unwrap_or(start);
+
         let ts = compiler.with_info(ts, position, None);
 
         Ok(ts)
@@ -1,5 +1,6 @@
 use crate::{
     compiler::{Compiler, Component},
+    escaper,
     node::{Function, Node},
     position::Position,
 };
@@ -21,6 +22,7 @@ impl TemplateCompiler {
         _position: Position,
     ) -> Result<TokenStream> {
         let fn_name = Ident::new(&compiler.generate_fn_name(&name), Span::call_site());
+        let is_root = compiler.is_root;
 
         let fn_call_ts = if compiler.is_root {
             compiler.is_root = false;
@@ -34,6 +36,19 @@ impl TemplateCompiler {
         if !compiler.components.contains_key(&path) {
             let prev_component_path = compiler.component_path.to_owned();
             compiler.component_path = path.to_owned();
+            // A component starts in html text, whatever surrounds its use.
+            let prev_html_context = std::mem::take(&mut compiler.html_context);
+            // and is escaped for the format of its own extension, the root template
+            // keeps the one chosen for the struct.
+            let component_escaper = if is_root {
+                compiler.escaper.clone()
+            } else {
+                escaper::by_extension(&path.to_string_lossy())
+                    .map(escaper::escaper)
+                    .transpose()?
+                    .flatten()
+            };
+            let prev_escaper = std::mem::replace(&mut compiler.escaper, component_escaper);
 
             compiler.components.insert(
                 path.to_owned(),
@@ -84,6 +99,8 @@ impl TemplateCompiler {
             }
 
             compiler.component_path = prev_component_path;
+            compiler.html_context = prev_html_context;
+            compiler.escaper = prev_escaper;
         }
 
         fn_call_ts
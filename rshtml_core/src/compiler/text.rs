@@ -9,6 +9,7 @@ pub struct TextCompiler;
 impl TextCompiler {
     pub fn compile(compiler: &mut Compiler, text: String) -> Result<TokenStream> {
         compiler.text_size.add_assign(text.len());
+        compiler.html_context.scan(&text);
         Ok(quote! { write!(__f__, "{}", #text)?; })
     }
 }
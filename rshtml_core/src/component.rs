@@ -0,0 +1,149 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    FnArg, GenericParam, Generics, Ident, ItemFn, Lifetime, Pat, Type, TypeImplTrait,
+    TypeReference, parse_quote, parse2, visit_mut::VisitMut,
+};
+
+/// `#[component]` on a function with typed props and an optional `children: impl View`,
+/// making it usable as a `v!` tag, e.g. `<Badge label={name}>children</Badge>`.
+///
+/// The function is kept as it is, next to a struct of the same name holding its props,
+/// whose `Component` impl calls the function with clones of the props.
+pub fn compile(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new_spanned(attr, "`#[component]` takes no arguments")
+            .to_compile_error();
+    }
+
+    match parse2::<ItemFn>(item).and_then(component) {
+        Ok(ts) => ts,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn component(item: ItemFn) -> syn::Result<TokenStream> {
+    let name = &item.sig.ident;
+    let vis = &item.vis;
+
+    let mut props = PropTypes::default();
+    let mut field_types = Vec::new();
+    let mut fields = Vec::new();
+    let mut args = Vec::new();
+
+    for input in &item.sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            return Err(syn::Error::new_spanned(
+                input,
+                "a component is a free function, it cannot take `self`",
+            ));
+        };
+        let Pat::Ident(pat) = &*arg.pat else {
+            return Err(syn::Error::new_spanned(
+                &arg.pat,
+                "component props have to be named, e.g. `label: &str`",
+            ));
+        };
+
+        let ident = &pat.ident;
+        if ident == "children" {
+            args.push(quote! { children });
+            continue;
+        }
+
+        let mut ty = (*arg.ty).clone();
+        props.visit_type_mut(&mut ty);
+        fields.push(quote! { #vis #ident: #ty });
+        field_types.push(ty);
+        args.push(quote! { ::std::clone::Clone::clone(&self.#ident) });
+    }
+
+    let generics = props.generics(&item.sig.generics);
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    // The props are cloned for every render, so their types have to be `Clone`.
+    let mut clone_generics = generics.clone();
+    let predicates = &mut clone_generics.make_where_clause().predicates;
+    for ty in &field_types {
+        predicates.push(parse_quote! { #ty: ::std::clone::Clone });
+    }
+    let clone_where_clause = &clone_generics.where_clause;
+
+    Ok(quote! {
+        #[allow(non_snake_case)]
+        #item
+
+        #vis struct #name #impl_generics #where_clause {
+            #(#fields,)*
+        }
+
+        impl #impl_generics ::rshtml::traits::Component for #name #type_generics
+        #clone_where_clause
+        {
+            fn render(
+                &self,
+                children: &dyn ::rshtml::traits::View,
+                out: &mut dyn ::std::fmt::Write,
+            ) -> ::std::fmt::Result {
+                ::rshtml::traits::View::render(&#name(#(#args),*), out)
+            }
+        }
+    })
+}
+
+// The props types as struct fields: elided lifetimes become a lifetime of the struct
+// and `impl Trait` types become type parameters.
+#[derive(Default)]
+struct PropTypes {
+    lifetime: Option<Lifetime>,
+    impl_params: Vec<(Ident, TypeImplTrait)>,
+}
+
+impl PropTypes {
+    fn lifetime(&mut self) -> Lifetime {
+        self.lifetime
+            .get_or_insert_with(|| Lifetime::new("'__props", Span::call_site()))
+            .clone()
+    }
+
+    fn generics(&self, fn_generics: &Generics) -> Generics {
+        let mut generics = fn_generics.clone();
+
+        if let Some(lifetime) = &self.lifetime {
+            generics.params.insert(0, parse_quote! { #lifetime });
+        }
+        for (ident, impl_trait) in &self.impl_params {
+            let bounds = &impl_trait.bounds;
+            generics
+                .params
+                .push(GenericParam::Type(parse_quote! { #ident: #bounds }));
+        }
+
+        generics
+    }
+}
+
+impl VisitMut for PropTypes {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::ImplTrait(impl_trait) = ty {
+            let ident = format_ident!("__P{}", self.impl_params.len());
+            self.impl_params.push((ident.clone(), impl_trait.clone()));
+            *ty = parse_quote! { #ident };
+        } else {
+            syn::visit_mut::visit_type_mut(self, ty);
+        }
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.lifetime());
+        }
+        syn::visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.lifetime();
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::{Result, anyhow};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Path, parse_str};
+
+/// The `rshtml::Escaper` named in `escape = ".."`, `None` for html, whose expressions
+/// are escaped for the html context they are in.
+///
+/// The name is one of the built-in formats, `html`, `xml`, `text` or `json`,
+/// or the path of a unit struct implementing `Escaper`.
+pub fn escaper(name: &str) -> Result<Option<TokenStream>> {
+    let escaper = match name {
+        "html" => return Ok(None),
+        "xml" => quote! { ::rshtml::escaper::Xml },
+        "text" | "txt" => quote! { ::rshtml::escaper::Text },
+        "json" => quote! { ::rshtml::escaper::Json },
+        path => {
+            let path = parse_str::<Path>(path).map_err(|_| {
+                anyhow!("unknown escaper `{path}`, expected html, xml, text, json or a path")
+            })?;
+            quote! { #path }
+        }
+    };
+
+    Ok(Some(escaper))
+}
+
+/// The format of a template from its extension, e.g. `feed.rs.xml` or `mail.rs.txt`.
+pub fn by_extension(template_name: &str) -> Option<&'static str> {
+    let (_, extension) = template_name.rsplit_once(".rs.")?;
+
+    match extension {
+        "xml" => Some("xml"),
+        "txt" => Some("text"),
+        "json" => Some("json"),
+        _ => None,
+    }
+}
@@ -2,9 +2,11 @@
 
 mod analyzer;
 mod compiler;
+pub mod component;
 pub mod config;
 mod diagnostic;
 mod error;
+mod escaper;
 mod node;
 mod parser;
 mod position;
@@ -28,18 +30,31 @@ pub fn process_template(
     struct_generics: &Generics,
     struct_fields: Vec<String>,
     no_warn: bool,
+    adaptive_size: bool,
+    escape: Option<String>,
 ) -> TokenStream {
     let config = Config::load_from_toml_or_default();
     let extract_file_on_debug = config.extract_file_on_debug;
 
-    let (compiled_ast_tokens, text_size, components) = match parse_and_compile(
-        &template_name,
-        config,
-        struct_name,
-        struct_generics,
-        struct_fields,
-        no_warn,
-    ) {
+    // An explicit `escape` wins over the one inferred from the template extension.
+    let escape = escape
+        .as_deref()
+        .or_else(|| escaper::by_extension(&template_name));
+
+    let (compiled_ast_tokens, text_size, components) = match escape
+        .map(escaper::escaper)
+        .transpose()
+        .and_then(|escaper| {
+            parse_and_compile(
+                &template_name,
+                config,
+                struct_name,
+                struct_generics,
+                struct_fields,
+                no_warn,
+                escaper.flatten(),
+            )
+        }) {
         Ok(tokens) => tokens,
         Err(err) => {
             let error_message = format!(
@@ -52,6 +67,26 @@ pub fn process_template(
 
     let text_size = text_size + ((text_size as f64 * 0.10) as usize).clamp(32, 512);
 
+    // With `adaptive_size` the buffer is sized from the previous render, the static estimate is only used for the first one.
+    // A `static` is shared by all instantiations of a generic impl, so structs with type or const parameters keep the estimate.
+    let generic = struct_generics.type_params().next().is_some()
+        || struct_generics.const_params().next().is_some();
+    let render_body = if adaptive_size && !generic {
+        quote! {
+            static SIZE: ::rshtml::AdaptiveSize = ::rshtml::AdaptiveSize::new(#text_size);
+            let mut buf = String::with_capacity(SIZE.get());
+            self.fmt(&mut buf)?;
+            SIZE.record(buf.len());
+            Ok(buf)
+        }
+    } else {
+        quote! {
+            let mut buf = String::with_capacity(#text_size);
+            self.fmt(&mut buf)?;
+            Ok(buf)
+        }
+    };
+
     let (impl_generics, type_generics, where_clause) = struct_generics.split_for_impl();
 
     // dbg!("DEBUG: Generated write_calls TokenStream:\n{}", compiled_ast_tokens.to_string());
@@ -84,9 +119,7 @@ pub fn process_template(
                 }
 
                 fn render(&self) -> Result<String, ::std::fmt::Error> {
-                    let mut buf = String::with_capacity(#text_size);
-                    self.fmt(&mut buf)?;
-                    Ok(buf)
+                    #render_body
                 }
             }
         };
@@ -111,6 +144,7 @@ fn parse_and_compile(
     struct_generics: &Generics,
     struct_fields: Vec<String>,
     no_warn: bool,
+    escaper: Option<TokenStream>,
 ) -> Result<(TokenStream, usize, TokenStream)> {
     let mut rshtml_parser = RsHtmlParser::new();
     let node = rshtml_parser.run(template_path, config)?;
@@ -127,6 +161,7 @@ fn parse_and_compile(
         struct_name.to_owned(),
         struct_generics.to_owned(),
         analyzer.diagnostic,
+        escaper,
     );
     let ts = compiler.run(node)?;
 
@@ -34,9 +34,11 @@ pub enum Node {
     MatchExpr(String, Vec<(String, Position, Vec<Node>)>, Position), // @match expr { ... => ... }
     RustExpr(Vec<(String, Position, Vec<Node>)>, Position), // @if ...  { ... } else { ... } / @for ... { ... }
     Component(String, Vec<ComponentParameter>, Vec<Node>, Position), // <ComponentName param1 = value1, param2 = value2> tags
-    ChildContent, // @child_content (component child content)
-    Raw(String),  // @raw {} (raw content)
+    ChildContent,                            // @child_content (component child content)
+    Raw(String),                             // @raw {} (raw content)
+    CacheBlock(String, Vec<Node>, Position), // @cache(key, store = .., ttl = ..) { ... } (arguments with parentheses, body)
     UseDirective(String, PathBuf, Box<Node>, Position), // @use "component.rs.html" as Component
-    ContinueDirective, // @continue for the loops
-    BreakDirective, // @break for the loops
+    ContinueDirective,                       // @continue for the loops
+    BreakDirective,                          // @break for the loops
+    FlushDirective,                          // @flush, ends the current chunk of a streaming render
 }
@@ -1,4 +1,5 @@
 mod block;
+mod cache_block;
 mod component;
 mod inner_text;
 mod match_expr;
@@ -17,11 +18,12 @@ use crate::{
     error::{E, rename_rules},
     node::*,
     parser::{
-        block::BlockParser, component::ComponentParser, inner_text::InnerTextParser,
-        match_expr::MatchExprParser, raw_block::RawBlockParser, rust_block::RustBlockParser,
-        rust_expr::RustExprParser, rust_expr_paren::RustExprParenParser,
-        rust_expr_simple::RustExprSimpleParser, template::TemplateParser,
-        template_params::TemplateParamsParser, text::TextParser, use_directive::UseDirectiveParser,
+        block::BlockParser, cache_block::CacheBlockParser, component::ComponentParser,
+        inner_text::InnerTextParser, match_expr::MatchExprParser, raw_block::RawBlockParser,
+        rust_block::RustBlockParser, rust_expr::RustExprParser,
+        rust_expr_paren::RustExprParenParser, rust_expr_simple::RustExprSimpleParser,
+        template::TemplateParser, template_params::TemplateParamsParser, text::TextParser,
+        use_directive::UseDirectiveParser,
     },
 };
 use pest::{
@@ -90,9 +92,11 @@ impl RsHtmlParser {
             Rule::component => ComponentParser::parse(self, pair),
             Rule::child_content_directive => Ok(Node::ChildContent),
             Rule::raw_block => RawBlockParser::parse(self, pair),
+            Rule::cache_block => CacheBlockParser::parse(self, pair),
             Rule::use_directive => UseDirectiveParser::parse(self, pair),
             Rule::continue_directive => Ok(Node::ContinueDirective),
             Rule::break_directive => Ok(Node::BreakDirective),
+            Rule::flush_directive => Ok(Node::FlushDirective),
             rule => Err(E::mes(format!("Error: Unknown rule: {rule:?}")).span(pair.as_span())),
         }
     }
@@ -157,7 +161,9 @@ impl RsHtmlParser {
 
     fn extract_component_name(&self, path: &Path) -> Option<String> {
         let filename = path.file_name().and_then(|n| n.to_str())?;
-        let component_name = filename.strip_suffix(".rs.html").unwrap_or(filename);
+        let component_name = filename
+            .rsplit_once(".rs.")
+            .map_or(filename, |(name, _)| name);
         Some(component_name.to_owned())
     }
 }
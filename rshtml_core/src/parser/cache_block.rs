@@ -0,0 +1,29 @@
+use crate::Node;
+use crate::error::E;
+use crate::parser::{IParser, RsHtmlParser, Rule};
+use crate::position::Position;
+use pest::error::Error;
+use pest::iterators::Pair;
+
+pub struct CacheBlockParser;
+
+impl IParser for CacheBlockParser {
+    fn parse(parser: &mut RsHtmlParser, pair: Pair<Rule>) -> Result<Node, Box<Error<Rule>>> {
+        let pair_span = pair.as_span();
+        let position = Position::from(&pair);
+        let mut inner_pairs = pair.into_inner();
+
+        let key = inner_pairs
+            .find(|p| p.as_rule() == Rule::cache_key)
+            .map(|p| p.as_str().to_string())
+            .ok_or(E::pos(Rule::cache_key).span(pair_span))?;
+
+        let body = inner_pairs
+            .find(|p| p.as_rule() == Rule::inner_template)
+            .ok_or(E::pos(Rule::inner_template).span(pair_span))?;
+
+        let body_nodes = parser.build_nodes_from_pairs(body.into_inner())?;
+
+        Ok(Node::CacheBlock(key, body_nodes, position))
+    }
+}
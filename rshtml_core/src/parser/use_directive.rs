@@ -1,5 +1,6 @@
 use crate::Node;
 use crate::error::E;
+use crate::escaper::by_extension;
 use crate::parser::{IParser, RsHtmlParser, Rule};
 use crate::position::Position;
 use pest::error::Error;
@@ -19,7 +20,7 @@ impl IParser for UseDirectiveParser {
             .ok_or(E::pos(Rule::string_line).span(pair_span))?;
 
         let mut import_path_str = import_path_str.as_str().trim_matches('"').to_string();
-        if !import_path_str.ends_with(".rs.html") {
+        if !import_path_str.ends_with(".rs.html") && by_extension(&import_path_str).is_none() {
             import_path_str.push_str(".rs.html");
         }
         let import_path = Path::new(&import_path_str);
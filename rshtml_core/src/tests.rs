@@ -4,7 +4,7 @@ mod viewer;
 use crate::config::Config;
 use crate::node::Node;
 use crate::parser::{RsHtmlParser, Rule};
-use crate::{parse_and_compile, process_template};
+use crate::{parse_and_compile, process_template, v_macro};
 use pest::Parser;
 use std::fs;
 use syn::__private::Span;
@@ -50,6 +50,8 @@ pub fn test_process_simple() {
         &Generics::default(),
         Vec::new(),
         true,
+        false,
+        None,
     );
 }
 
@@ -63,6 +65,7 @@ pub fn test_parse_and_compile() {
         &Generics::default(),
         Vec::new(),
         false,
+        None,
     )
     .unwrap();
 }
@@ -72,3 +75,83 @@ pub fn test_config() {
     let config = Config::default();
     assert!(config.base_path.ends_with("views"));
 }
+
+#[test]
+pub fn test_v_macro_structure() {
+    let compile = |input: proc_macro2::TokenStream| v_macro::compile(input).to_string();
+
+    assert!(
+        !compile(quote::quote!(<div><br><img src="a.png"/><p/></div>)).contains("compile_error")
+    );
+    assert!(
+        compile(quote::quote!(<div><span></div>))
+            .contains("mismatched closing tag `</div>`, expected `</span>`")
+    );
+    assert!(compile(quote::quote!(<div><span></span>)).contains("unclosed tag `<div>`"));
+    assert!(compile(quote::quote!(<p></p></div>)).contains("unexpected closing tag `</div>`"));
+    assert!(
+        compile(quote::quote!(<div>@if true { <span> }</div>)).contains("unclosed tag `<span>`")
+    );
+    assert!(compile(quote::quote!(<br></br>)).contains("void element"));
+}
+
+#[test]
+pub fn test_v_macro_errors() {
+    let compile = |input: proc_macro2::TokenStream| v_macro::compile(input).to_string();
+
+    assert!(
+        compile(quote::quote!(<div><Card title={x}</div>))
+            .contains("component: expected one of `/`, `>`, found `<`")
+    );
+    assert!(
+        compile(quote::quote!(@match x { 1 => <p></p> }))
+            .contains("match arm: expected `{`, found `<`")
+    );
+    assert!(compile(quote::quote!(@for x in y)).contains("expected `{`, found end of input"));
+
+    let unclosed = compile(quote::quote!(@if x { <Card> }));
+    assert!(unclosed.contains("expected `</Card>`, found end of input"));
+    assert!(unclosed.contains("note: unclosed `<Card>` opened here"));
+
+    let mismatched = compile(quote::quote!(<div><span></section>));
+    assert!(mismatched.contains("mismatched closing tag `</section>`, expected `</span>`"));
+    assert!(mismatched.contains("note: unclosed `<span>` opened here"));
+}
+
+#[test]
+pub fn test_v_file_error_position() {
+    let output = v_macro::compile_file(quote::quote!("mismatched.html")).to_string();
+
+    assert!(output.contains(
+        "mismatched.html:3:21: compile error: mismatched closing tag `</lo>`, expected `</li>`"
+    ));
+    assert!(output.contains("mismatched.html:3:10: note: unclosed `<li>` opened here"));
+}
+
+#[test]
+pub fn test_v_macro_error_span() {
+    proc_macro2::fallback::force();
+    let input: proc_macro2::TokenStream = "<div>\n  <p>@if x {\n  1 }</p>\n</section>"
+        .parse()
+        .unwrap();
+    let output = v_macro::compile(input);
+    proc_macro2::fallback::unforce();
+
+    // compile_error! is spanned at the `section` name of the closing tag.
+    let error = output
+        .into_iter()
+        .flat_map(flatten)
+        .find(|tt| tt.to_string() == "compile_error")
+        .unwrap();
+    let start = error.span().start();
+    assert_eq!((start.line, start.column), (4, 2));
+}
+
+fn flatten(tt: proc_macro2::TokenTree) -> Vec<proc_macro2::TokenTree> {
+    match tt {
+        proc_macro2::TokenTree::Group(group) => {
+            group.stream().into_iter().flat_map(flatten).collect()
+        }
+        tt => vec![tt],
+    }
+}
@@ -91,6 +91,12 @@ pub fn view_node(node: &Node, indent: usize) {
             println!("- ChildContent");
         }
         Node::Raw(s) => println!("- Raw: {s:?}"),
+        Node::CacheBlock(key, body, _) => {
+            println!("- CacheBlock: {key:?}");
+            for inner_node in body {
+                view_node(inner_node, indent + 1);
+            }
+        }
         Node::UseDirective(component_name, import_path, component, _) => {
             println!("- UseDirective:");
             print_indent(indent + 1);
@@ -107,5 +113,8 @@ pub fn view_node(node: &Node, indent: usize) {
         Node::BreakDirective => {
             println!("- BreakDirective");
         }
+        Node::FlushDirective => {
+            println!("- FlushDirective");
+        }
     }
 }
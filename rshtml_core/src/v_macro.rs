@@ -1,92 +1,592 @@
-use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
-use quote::{format_ident, quote};
+mod error;
+mod source;
+
+use crate::config::Config;
+use error::{Diagnostic, ParseError};
+use proc_macro2::{Delimiter, Group, Ident, LineColumn, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+use std::{fs, str::FromStr};
 use syn::parse2;
 use winnow::ModalResult;
-use winnow::combinator::{alt, eof, opt, repeat, repeat_till, terminated};
-use winnow::error::{StrContext, StrContextValue};
+use winnow::combinator::{alt, cut_err, eof, opt, preceded, repeat, repeat_till, terminated};
+use winnow::error::{ErrMode, StrContext, StrContextValue};
 use winnow::{Parser, token::any};
 
-// TODO: Enable file reading using the v_file! macro.
+type PResult<O> = ModalResult<O, ParseError>;
 
 enum Node {
-    Expr(TokenStream),
+    Expr(TokenStream, Location),
+    AttributeExpr(String, TokenStream, Location), // class={expr}, the value renders the attribute
+    Attributes(Vec<Attribute>, Location), // attributes of a tag with {..spread}, merged at render time
+    Text(String, Location),
+    RustExpr(Vec<Clause>, Location), // @if ... { ... } else { ... } / @for ... { ... }
+    MatchExpr(TokenStream, Vec<Clause>, Location), // @match ... { ... => { ... } }
+    Element(Element),                // marks an open or close tag for validation, renders nothing
+    Component(TokenStream, Vec<(Ident, TokenStream)>, Vec<Node>, Location), // <Card title={x}>...</Card>
+    RawText(String, Vec<RawTextPart>, Location), // body of a <script> or <style>
+}
+
+enum RawTextPart {
     Text(String),
+    Expr(TokenStream),
+}
+
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+enum Element {
+    Open(String, Location),
+    Close(String, Location),
+}
+
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+fn is_void_element(tag_name: &str) -> bool {
+    VOID_ELEMENTS.contains(&tag_name.to_ascii_lowercase().as_str())
+}
+
+enum Attribute {
+    Text(Vec<Node>, String, Option<String>), // source nodes, name and value as written, e.g. "x"
+    Expr(String, TokenStream, Location),
+    Spread(TokenStream, Location),
+}
+
+// (head, body) of a control flow block, e.g. (`else if x > 1`, nodes) or (`Some(x)`, nodes)
+type Clause = (TokenStream, Vec<Node>);
+
+impl Node {
+    fn location(&self) -> Location {
+        match self {
+            Node::Expr(_, location)
+            | Node::AttributeExpr(_, _, location)
+            | Node::Attributes(_, location)
+            | Node::Text(_, location)
+            | Node::RustExpr(_, location)
+            | Node::MatchExpr(_, _, location)
+            | Node::Component(_, _, _, location)
+            | Node::RawText(_, _, location) => *location,
+            Node::Element(Element::Open(_, location) | Element::Close(_, location)) => *location,
+        }
+    }
+}
+
+// Where a node is in the source, errors are reported at `span`, the span of its first token.
+#[derive(Debug, Clone, Copy)]
+struct Location {
+    span: Span,
+    start: LineColumn,
+    end: LineColumn,
+}
+
+impl Location {
+    fn of(tokens: &[TokenTree]) -> Self {
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => Self::token(first).to(Self::token(last)),
+            _ => Span::call_site().into(),
+        }
+    }
+
+    // The tokens of a `v_file!` source are found in the file, the others by their span.
+    fn token(tt: &TokenTree) -> Self {
+        source::position(tt).map_or_else(
+            || tt.span().into(),
+            |position| position.open.to(position.close),
+        )
+    }
+
+    fn delimiters(tt: &TokenTree, group: &Group) -> (Self, Self) {
+        source::position(tt).map_or_else(
+            || (group.span_open().into(), group.span_close().into()),
+            |position| (position.open, position.close),
+        )
+    }
+
+    fn to(self, other: Location) -> Self {
+        Self {
+            span: self.span,
+            start: self.start,
+            end: other.end,
+        }
+    }
+
+    // Without span locations (e.g. older compilers) every position is 0:0.
+    fn is_known(&self) -> bool {
+        self.start.line != 0
+    }
+
+    fn whitespace_until(&self, next: &Location) -> String {
+        // Tokens that do not follow each other in the source (e.g. all sharing the
+        // call site span) carry no layout, so they fall back to a single space.
+        if !self.is_known()
+            || !next.is_known()
+            || (next.start.line, next.start.column) < (self.end.line, self.end.column)
+        {
+            return " ".to_owned();
+        }
+
+        if next.start.line > self.end.line {
+            let mut ws = "\n".repeat(next.start.line - self.end.line);
+            ws.push_str(&" ".repeat(next.start.column));
+            ws
+        } else {
+            " ".repeat(next.start.column.saturating_sub(self.end.column))
+        }
+    }
+}
+
+impl From<Span> for Location {
+    fn from(span: Span) -> Self {
+        Self {
+            span,
+            start: span.start(),
+            end: span.end(),
+        }
+    }
 }
 
 pub fn compile(input: TokenStream) -> TokenStream {
     let tokens: Vec<TokenTree> = input.into_iter().collect();
-    let mut tokens = tokens.as_slice();
-
-    let (expr_defs, body, text_size) = match terminated(template, eof).parse_next(&mut tokens) {
-        Ok((expr_defs, nodes)) => {
-            let mut body = TokenStream::new();
-            let mut text_buffer = String::new();
-            let mut first = true;
-            let mut text_size = 0;
-
-            for node in nodes {
-                match node {
-                    Node::Expr(tokens) => {
-                        if !text_buffer.is_empty() {
-                            text_buffer.push(' ');
-                            body.extend(quote! { write!(out, "{}", #text_buffer)?; });
-                            text_buffer.clear();
-                        }
 
-                        body.extend(tokens);
-                    }
-                    Node::Text(text) => {
-                        if !first {
-                            text_buffer.push(' ');
+    let (expr_defs, body, text_size) = match parse(&tokens) {
+        Ok(parsed) => parsed,
+        Err(diagnostic) => (TokenStream::new(), diagnostic.to_compile_error(), 0),
+    };
+
+    view_fn(expr_defs, body, text_size)
+}
+
+pub fn compile_file(input: TokenStream) -> TokenStream {
+    let path_lit = match parse2::<syn::LitStr>(input) {
+        Ok(path_lit) => path_lit,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let path = Config::load_from_toml_or_default()
+        .base_path
+        .join(path_lit.value());
+
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => {
+            let msg = format!("Error reading template: {err}, path: {}", path.display());
+            return quote_spanned! { path_lit.span() => compile_error!(#msg) };
+        }
+    };
+
+    let tokens: Vec<TokenTree> = match TokenStream::from_str(&source) {
+        Ok(tokens) => tokens.into_iter().collect(),
+        Err(err) => {
+            let msg = format!("{}: lex error: {err}", path.display());
+            return quote_spanned! { path_lit.span() => compile_error!(#msg) };
+        }
+    };
+
+    // The tokens all have the span of the call, their positions are taken from the file text.
+    let positions = source::locate(&source, &tokens);
+    let located = positions.is_some();
+
+    match source::scope(&tokens, positions, || parse(&tokens)) {
+        Ok((expr_defs, body, text_size)) => view_fn(expr_defs, body, text_size),
+        Err(diagnostic) => {
+            let position = |location: Location| {
+                if located {
+                    let start = location.start;
+                    format!("{}:{}:{}", path.display(), start.line, start.column + 1)
+                } else {
+                    path.display().to_string()
+                }
+            };
+
+            let mut msg = format!(
+                "{}: compile error: {}",
+                position(diagnostic.location),
+                diagnostic.message
+            );
+            for (location, note) in &diagnostic.notes {
+                msg.push_str(&format!("\n{}: note: {note}", position(*location)));
+            }
+
+            quote_spanned! { path_lit.span() => compile_error!(#msg) }
+        }
+    }
+}
+
+fn parse(tokens: &[TokenTree]) -> Result<(TokenStream, TokenStream, usize), Diagnostic> {
+    let input_end = tokens.last().map(Location::token);
+    let mut tokens = tokens;
+
+    // v!(escape = xml, ...) escapes the expressions with another `rshtml::Escaper` than html,
+    // a built-in format name or an expression of an `Escaper` value.
+    let escaper = match tokens {
+        [
+            TokenTree::Ident(ident),
+            equal @ TokenTree::Punct(punct),
+            rest @ ..,
+        ] if ident == "escape" && punct.as_char() == '=' => {
+            let end = rest
+                .iter()
+                .position(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == ','))
+                .filter(|&end| end > 0)
+                .ok_or_else(|| {
+                    Diagnostic::new(
+                        Location::token(equal),
+                        "expected an escaper and a `,`, e.g. `v!(escape = xml, ...)`",
+                    )
+                })?;
+            let escaper = &rest[..end];
+            tokens = &rest[end + 1..];
+
+            match escaper {
+                [TokenTree::Ident(name)] => match crate::escaper::escaper(&name.to_string()) {
+                    Ok(escaper) => escaper,
+                    Err(_) => Some(quote! { #name }),
+                },
+                escaper => Some(escaper.iter().cloned().collect()),
+            }
+        }
+        _ => None,
+    };
+
+    // v!(@lazy ...) evaluates the expressions when the view is rendered, not when it is built,
+    // a plain `lazy` is the text it reads.
+    let lazy = match tokens {
+        [TokenTree::Punct(at), TokenTree::Ident(ident), ..]
+            if at.as_char() == '@' && ident == "lazy" =>
+        {
+            tokens = &tokens[2..];
+            true
+        }
+        _ => false,
+    };
+
+    match terminated(template, eof).parse_next(&mut tokens) {
+        Ok(nodes) => {
+            validate(&nodes)?;
+
+            let mut body = Body {
+                lazy,
+                escaper,
+                ..Body::default()
+            };
+            let ts = body.compile(nodes, false);
+
+            Ok((body.expr_defs, ts, body.text_size))
+        }
+        Err(err) => {
+            let err = err.into_inner().unwrap();
+            let end = input_end.unwrap_or_else(|| Span::call_site().into());
+
+            Err(err.at_end(end).into_diagnostic())
+        }
+    }
+}
+
+#[derive(Default)]
+struct Body {
+    lazy: bool,
+    escaper: Option<TokenStream>, // None for html
+    expr_defs: TokenStream,
+    text_size: usize,
+    text_written: usize, // static text of all nodes, also those in control flow blocks
+    expr_count: usize,
+    prop_count: usize,
+}
+
+impl Body {
+    // Top level expressions are evaluated once, before the closure; expressions inside
+    // control flow blocks may depend on their bindings, so they are evaluated inline,
+    // as are all expressions in lazy mode.
+    fn compile(&mut self, nodes: Vec<Node>, is_inline: bool) -> TokenStream {
+        let mut ts = TokenStream::new();
+        let mut text_buffer = String::new();
+        let mut prev_location: Option<Location> = None;
+
+        for node in nodes {
+            if let Node::Element(_) = node {
+                continue;
+            }
+
+            let location = node.location();
+            let whitespace = prev_location
+                .map(|prev_location| prev_location.whitespace_until(&location))
+                .unwrap_or_default();
+            prev_location = Some(location);
+
+            match node {
+                Node::Text(text, _) => {
+                    text_buffer.push_str(&whitespace);
+                    text_buffer.push_str(&text);
+                    continue;
+                }
+                // The attribute value may drop the attribute, together with its leading whitespace.
+                Node::AttributeExpr(..) | Node::Attributes(..) => (),
+                _ => text_buffer.push_str(&whitespace),
+            }
+
+            self.write_text(&mut ts, &mut text_buffer, is_inline);
+
+            match node {
+                Node::Expr(expr, _) => {
+                    let value = self.value(expr, is_inline);
+                    ts.extend(match &self.escaper {
+                        Some(escaper) => {
+                            quote! { (&::rshtml::ExpWith(#escaper, &#value)).render(out)?; }
+                        }
+                        None => quote! { ::rshtml::Exp(&#value).render(out)?; },
+                    });
+                }
+                Node::AttributeExpr(name, expr, _) => {
+                    let value = self.value(expr, is_inline);
+                    let render = match &self.escaper {
+                        Some(escaper) => quote! {
+                            (&::rshtml::Attr(&#value))
+                                .render_attribute_escaped(#name, &#escaper, &mut _out)?;
+                        },
+                        None => quote! {
+                            (&::rshtml::Attr(&#value)).render_attribute(#name, &mut _out)?;
+                        },
+                    };
+                    ts.extend(quote! {
+                        {
+                            let mut _out = ::rshtml::AttrSeparator::new(#whitespace, out);
+                            #render
+                        }
+                    });
+                }
+                Node::Attributes(attributes, _) => {
+                    let attributes = self.merge_attributes(attributes, is_inline);
+                    ts.extend(quote! {
+                        {
+                            let mut _attributes = ::rshtml::Attributes::new();
+                            #attributes
+                            ::rshtml::traits::View::render(&_attributes, out)?;
                         }
-                        text_buffer.push_str(&text);
-                        text_size += text.len();
-                        first = false;
+                    });
+                }
+                Node::RawText(tag_name, parts, _) => {
+                    for part in parts {
+                        match part {
+                            RawTextPart::Text(text) => text_buffer.push_str(&text),
+                            RawTextPart::Expr(expr) => {
+                                self.write_text(&mut ts, &mut text_buffer, is_inline);
+
+                                let value = self.value(expr, is_inline);
+                                ts.extend(if tag_name == "style" {
+                                    quote! { ::rshtml::Css(&#value).render(out)?; }
+                                } else {
+                                    quote! { ::rshtml::Js(&#value).render(out)?; }
+                                });
+                            }
+                        }
+                    }
+                }
+                // The component owns its props, so they are cloned from their bindings for every
+                // render and the view stays `Fn`, also when a prop is an owned value.
+                Node::Component(path, props, children, _) => {
+                    let props = props
+                        .into_iter()
+                        .map(|(name, value)| {
+                            let value = self.prop(value, is_inline);
+                            quote! { #name: (&#value).clone() }
+                        })
+                        .collect::<Vec<_>>();
+                    // The children view is sized from their text and their top level expressions.
+                    let (text_before, exprs_before) = (self.text_written, self.expr_count);
+                    let children = self.compile(children, is_inline);
+                    let text_size = self.text_written - text_before;
+                    let exprs = (exprs_before..self.expr_count).map(|i| format_ident!("_exp{i}"));
+
+                    ts.extend(quote! {
+                        ::rshtml::traits::Component::render(
+                            &#path { #(#props),* },
+                            &::rshtml::ViewFn::new((
+                                |out: &mut dyn ::std::fmt::Write| -> ::std::fmt::Result {
+                                    #children
+                                    Ok(())
+                                },
+                                #text_size #(+ ::rshtml::TextSize(&#exprs).text_size())*,
+                            )),
+                            out,
+                        )?;
+                    });
+                }
+                Node::RustExpr(clauses, _) => {
+                    for (head, nodes) in clauses {
+                        let body = self.compile(nodes, true);
+                        ts.extend(quote! { #head { #body } });
                     }
                 }
+                Node::MatchExpr(head, arms, _) => {
+                    let arms = arms.into_iter().map(|(pattern, nodes)| {
+                        let body = self.compile(nodes, true);
+                        quote! { #pattern => { #body } }
+                    });
+                    let arms = arms.collect::<Vec<_>>();
+
+                    ts.extend(quote! { #head { #(#arms)* } });
+                }
+                Node::Text(..) | Node::Element(_) => unreachable!(),
             }
+        }
+
+        self.write_text(&mut ts, &mut text_buffer, is_inline);
+
+        ts
+    }
+
+    // Later attributes replace earlier ones, except `class` values which are joined.
+    fn merge_attributes(&mut self, attributes: Vec<Attribute>, is_inline: bool) -> TokenStream {
+        let mut ts = TokenStream::new();
+
+        for attribute in attributes {
+            match attribute {
+                Attribute::Text(_, name, value) => {
+                    let value = value.as_deref().map(literal_value);
+                    if !is_inline {
+                        self.text_size +=
+                            1 + name.len() + value.as_ref().map_or(0, |v| v.len() + 3);
+                    }
 
-            if !text_buffer.is_empty() {
-                body.extend(quote! { write!(out, "{}", #text_buffer)?; });
+                    let value = match value {
+                        Some(value) => quote! { Some(#value) },
+                        None => quote! { None },
+                    };
+                    ts.extend(quote! { _attributes.merge_html(#name, #value); });
+                }
+                Attribute::Expr(name, expr, _) => {
+                    let value = self.value(expr, is_inline);
+                    let escaper = match &self.escaper {
+                        Some(escaper) => quote! { Some(&#escaper) },
+                        None => quote! { None },
+                    };
+                    ts.extend(quote! {
+                        _attributes.merge_value(
+                            #name,
+                            (&::rshtml::Attr(&#value)).to_attribute(#name, #escaper)?,
+                        );
+                    });
+                }
+                Attribute::Spread(expr, _) => {
+                    let value = self.value(expr, is_inline);
+                    ts.extend(quote! { _attributes.merge(&#value); });
+                }
             }
+        }
+
+        ts
+    }
 
-            (expr_defs, body, text_size)
+    fn value(&mut self, expr: TokenStream, is_inline: bool) -> TokenStream {
+        if is_inline || self.lazy {
+            return expr;
         }
-        Err(e) => {
-            let span = tokens
-                .first()
-                .map(|tt| tt.span())
-                .unwrap_or_else(Span::call_site);
-
-            let err = e.into_inner().unwrap();
-            let msg = err
-                .context()
-                .filter_map(|c| match c {
-                    StrContext::Label(l) => Some(l.to_string()),
-                    StrContext::Expected(e) => Some(format!("expected {}", e)),
-                    _ => None,
-                })
-                .collect::<Vec<_>>()
-                .into_iter()
-                .rev()
-                .collect::<Vec<_>>()
-                .join(": ");
-
-            let msg = format!("compile error: {msg}");
-            let msg_lit = syn::LitStr::new(&msg, span);
 
-            (
-                TokenStream::new(),
-                quote::quote_spanned! { span =>
-                    compile_error!(#msg_lit);
-                },
-                0,
-            )
+        let def_ident = format_ident!("_exp{}", self.expr_count);
+        self.expr_count += 1;
+
+        self.expr_defs.extend(quote! {
+            let #def_ident = #expr;
+            _text_size += ::rshtml::TextSize(&#def_ident).text_size();
+        });
+
+        quote! { (#def_ident) }
+    }
+
+    // Like `value`, but a prop is not rendered here, so it adds nothing to the text size.
+    fn prop(&mut self, expr: TokenStream, is_inline: bool) -> TokenStream {
+        if is_inline || self.lazy {
+            return quote! { (#expr) };
         }
-    };
 
+        let def_ident = format_ident!("_prop{}", self.prop_count);
+        self.prop_count += 1;
+
+        self.expr_defs.extend(quote! { let #def_ident = #expr; });
+
+        quote! { #def_ident }
+    }
+
+    fn write_text(&mut self, ts: &mut TokenStream, text_buffer: &mut String, is_inline: bool) {
+        if text_buffer.is_empty() {
+            return;
+        }
+
+        if !is_inline {
+            self.text_size += text_buffer.len();
+        }
+        self.text_written += text_buffer.len();
+
+        ts.extend(quote! { write!(out, "{}", #text_buffer)?; });
+        text_buffer.clear();
+    }
+}
+
+// Every element opened in a block has to be closed in the same block, e.g. in an `@if` body.
+fn validate(nodes: &[Node]) -> Result<(), Diagnostic> {
+    let mut stack: Vec<(&str, Location)> = Vec::new();
+
+    for node in nodes {
+        match node {
+            Node::Element(Element::Open(tag_name, location)) if !is_void_element(tag_name) => {
+                stack.push((tag_name, *location));
+            }
+            Node::Element(Element::Close(tag_name, location)) => {
+                if is_void_element(tag_name) {
+                    return Err(Diagnostic::new(
+                        *location,
+                        format!("`<{tag_name}>` is a void element and cannot have a closing tag"),
+                    ));
+                }
+
+                match stack.pop() {
+                    Some((open_name, _)) if open_name == tag_name => (),
+                    Some((open_name, open_location)) => {
+                        return Err(Diagnostic::new(
+                            *location,
+                            format!(
+                                "mismatched closing tag `</{tag_name}>`, expected `</{open_name}>`"
+                            ),
+                        )
+                        .with_note(
+                            open_location,
+                            format!("unclosed `<{open_name}>` opened here"),
+                        ));
+                    }
+                    None => {
+                        return Err(Diagnostic::new(
+                            *location,
+                            format!("unexpected closing tag `</{tag_name}>`, no element is open"),
+                        ));
+                    }
+                }
+            }
+            Node::RustExpr(clauses, _) | Node::MatchExpr(_, clauses, _) => {
+                for (_, nodes) in clauses {
+                    validate(nodes)?;
+                }
+            }
+            Node::Component(_, _, children, _) => validate(children)?,
+            _ => (),
+        }
+    }
+
+    match stack.pop() {
+        Some((tag_name, location)) => Err(Diagnostic::new(
+            location,
+            format!("unclosed tag `<{tag_name}>`"),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn view_fn(expr_defs: TokenStream, body: TokenStream, text_size: usize) -> TokenStream {
     quote! {
         ::rshtml::ViewFn::new({
+            #[allow(unused_imports)]
+            use ::rshtml::{AttrView as _, ExpWithView as _, traits::{AttributeValue as _, View as _}};
+
             let mut _text_size = #text_size;
             #expr_defs
 
@@ -101,28 +601,118 @@ pub fn compile(input: TokenStream) -> TokenStream {
     }
 }
 
-fn template(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, Vec<Node>)> {
-    repeat(
-        0..,
-        alt((
-            expr.map(|(ts, expr)| (ts, vec![Node::Expr(expr)])),
-            group,
-            text.map(|t| (TokenStream::new(), vec![Node::Text(t)])),
-            tag,
-        )),
-    )
-    .fold(
-        || (TokenStream::new(), Vec::new()),
-        |(mut expr_defs, mut bodies), (expr_def, body)| {
-            expr_defs.extend(expr_def);
-            bodies.extend(body);
-            (expr_defs, bodies)
-        },
-    )
+fn template(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
+    repeat(0.., template_item)
+        .fold(Vec::new, |mut nodes, node| {
+            nodes.extend(node);
+            nodes
+        })
+        .parse_next(input)
+}
+
+fn template_item(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
+    alt((
+        rust_expr
+            .with_taken()
+            .map(|(clauses, taken)| vec![Node::RustExpr(clauses, Location::of(taken))]),
+        match_expr
+            .with_taken()
+            .map(|((head, arms), taken)| vec![Node::MatchExpr(head, arms, Location::of(taken))]),
+        expr.with_taken()
+            .map(|(expr, taken)| vec![Node::Expr(expr, Location::of(taken))]),
+        group,
+        text,
+        raw_text_element,
+        component
+            .with_taken()
+            .map(|((path, props, children), taken)| {
+                vec![Node::Component(path, props, children, Location::of(taken))]
+            }),
+        tag,
+    ))
     .parse_next(input)
 }
 
-fn expr(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, TokenStream)> {
+type ComponentParts = (TokenStream, Vec<(Ident, TokenStream)>, Vec<Node>);
+
+// <Card title={x}>children</Card> or <ui::Card/>, the attributes become the fields of `Card`.
+fn component(input: &mut &[TokenTree]) -> PResult<ComponentParts> {
+    let ((_, (path, name)), start) = (lt, component_path).with_taken().parse_next(input)?;
+    let start = Location::of(&start[1..2]);
+
+    let props = repeat(0.., attribute.verify_map(component_prop)).parse_next(input)?;
+
+    let self_closing = cut_err(alt(((slash, gt).value(true), gt.value(false))))
+        .context(StrContext::Label("component"))
+        .parse_next(input)?;
+
+    if self_closing {
+        return Ok((path, props, Vec::new()));
+    }
+
+    let close =
+        (lt, slash, component_path, gt).verify(|(_, _, (_, close_name), _)| *close_name == name);
+    let (children, _): (Vec<Vec<Node>>, _) = cut_err(repeat_till(0.., template_item, close))
+        .parse_next(input)
+        .map_err(|err| {
+            err.map(|err| {
+                err.expecting(format!("`</{name}>`"))
+                    .with_note(start, format!("unclosed `<{name}>` opened here"))
+            })
+        })?;
+
+    Ok((path, props, children.into_iter().flatten().collect()))
+}
+
+// A path whose last segment starts with an uppercase letter, e.g. `Card` or `ui::Card`.
+fn component_path(input: &mut &[TokenTree]) -> PResult<(TokenStream, String)> {
+    (ident, repeat(0.., (colon, colon, ident)))
+        .verify_map(|(first, rest): (TokenStream, Vec<_>)| {
+            let mut name = first.to_string();
+            let mut path = first;
+
+            for (colon1, colon2, ident) in rest {
+                let ident: TokenStream = ident;
+                name.push_str(&format!("::{ident}"));
+                path.extend([colon1, colon2, ident]);
+            }
+
+            let last = name.rsplit("::").next().unwrap_or_default();
+            last.starts_with(|c: char| c.is_ascii_uppercase())
+                .then_some((path, name))
+        })
+        .parse_next(input)
+}
+
+// Props are struct fields, so their names have to be rust identifiers.
+fn component_prop(attribute: Attribute) -> Option<(Ident, TokenStream)> {
+    match attribute {
+        Attribute::Expr(name, expr, _) => {
+            Some((parse2(TokenStream::from_str(&name).ok()?).ok()?, expr))
+        }
+        Attribute::Text(_, name, value) => {
+            let name = parse2(TokenStream::from_str(&name).ok()?).ok()?;
+            let value = match value {
+                Some(value)
+                    if value.starts_with('"')
+                        || value.starts_with(|c: char| c.is_ascii_digit()) =>
+                {
+                    TokenStream::from_str(&value).ok()?
+                }
+                Some(value) => {
+                    let value = unquote(&value);
+                    quote! { #value }
+                }
+                None => quote! { true },
+            };
+
+            Some((name, value))
+        }
+        Attribute::Spread(..) => None,
+    }
+}
+
+fn expr(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     let group: Group = any
         .verify_map(|tt: TokenTree| match tt {
             TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => Some(g),
@@ -132,122 +722,195 @@ fn expr(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, TokenStream)> {
 
     let stream = group.stream();
 
-    let def_ident = format_ident!("_exp{}", input.len());
-
     let output = if let Ok(expr) = parse2::<syn::Expr>(stream.clone()) {
-        (
-            quote! { let #def_ident = (#expr); _text_size += ::rshtml::TextSize(&#def_ident).text_size(); },
-            quote! { ::rshtml::Exp(&(#def_ident)).render(out)?; },
-        )
+        quote! { (#expr) }
     } else if let Ok(block) = parse2::<syn::Block>(stream.clone()) {
-        (
-            quote! { let #def_ident = {#block}; _text_size += ::rshtml::TextSize(&#def_ident).text_size(); },
-            quote! { ::rshtml::Exp(&(#def_ident)).render(out)?; },
-        )
+        quote! { {#block} }
     } else {
-        (
-            quote! { let #def_ident = {#stream}; _text_size += ::rshtml::TextSize(&#def_ident).text_size(); },
-            quote! { ::rshtml::Exp(&(#def_ident)).render(out)?; },
-        )
+        quote! { {#stream} }
     };
 
     Ok(output)
 }
 
-fn group(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, Vec<Node>)> {
-    let (tokens, (open, close)): (Vec<TokenTree>, (Node, Node)) = any
+fn group(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
+    let (group, taken) = any
         .verify_map(|tt: TokenTree| match tt {
             TokenTree::Group(g)
                 if matches!(g.delimiter(), Delimiter::Parenthesis | Delimiter::Bracket) =>
             {
-                Some((
-                    g.stream().into_iter().collect(),
-                    match g.delimiter() {
-                        Delimiter::Parenthesis => {
-                            (Node::Text("(".to_owned()), Node::Text(")".to_owned()))
-                        }
-                        Delimiter::Bracket => {
-                            (Node::Text("[".to_owned()), Node::Text("]".to_owned()))
-                        }
-                        _ => unreachable!(),
-                    },
-                ))
+                Some(g)
             }
             _ => None,
         })
+        .with_taken()
         .parse_next(input)?;
 
-    let mut tokens = tokens.as_slice();
+    let (open_text, close_text) = match group.delimiter() {
+        Delimiter::Parenthesis => ("(", ")"),
+        _ => ("[", "]"),
+    };
+    let (open_location, close_location) = Location::delimiters(&taken[0], &group);
+    let open = Node::Text(open_text.to_owned(), open_location);
+    let close = Node::Text(close_text.to_owned(), close_location);
 
-    let (inner_expr_defs, inner_nodes) = template.parse_next(&mut tokens)?;
+    let inner_nodes = parse_group(&taken[0], &group, |input| template.parse_next(input))?;
 
     let mut nodes = vec![open];
     nodes.extend(inner_nodes);
     nodes.push(close);
 
-    Ok((inner_expr_defs, nodes))
+    Ok(nodes)
+}
+
+fn rust_expr(input: &mut &[TokenTree]) -> PResult<Vec<Clause>> {
+    let start = preceded(at, alt((keyword("if"), keyword("for")))).parse_next(input)?;
+    let is_if = start == "if";
+
+    let (head, body) = cut_err(clause).parse_next(input)?;
+    let mut clauses = vec![(quote! { #start #head }, body)];
+
+    while is_if && let Some(else_keyword) = opt(keyword("else")).parse_next(input)? {
+        let (head, body) = cut_err(clause).parse_next(input)?;
+        clauses.push((quote! { #else_keyword #head }, body));
+    }
+
+    Ok(clauses)
+}
+
+fn match_expr(input: &mut &[TokenTree]) -> PResult<(TokenStream, Vec<Clause>)> {
+    let match_keyword = preceded(at, keyword("match")).parse_next(input)?;
+
+    let (head, (group, taken)) = cut_err((head, brace_group.with_taken()))
+        .context(StrContext::Label("match expression"))
+        .parse_next(input)?;
+
+    let arms = parse_group(&taken[0], &group, |input| {
+        cut_err(terminated(repeat(0.., match_arm), eof)).parse_next(input)
+    })?;
+
+    Ok((quote! { #match_keyword #head }, arms))
 }
 
-fn text(input: &mut &[TokenTree]) -> ModalResult<String> {
+fn match_arm(input: &mut &[TokenTree]) -> PResult<Clause> {
+    let (pattern, _): (Vec<TokenTree>, _) = repeat_till(1.., any, (equal, gt)).parse_next(input)?;
+    let body = cut_err(block)
+        .context(StrContext::Label("match arm"))
+        .parse_next(input)?;
+    opt(comma).parse_next(input)?;
+
+    Ok((TokenStream::from_iter(pattern), body))
+}
+
+fn clause(input: &mut &[TokenTree]) -> PResult<Clause> {
+    (head, block).parse_next(input)
+}
+
+fn head(input: &mut &[TokenTree]) -> PResult<TokenStream> {
+    repeat(
+        0..,
+        any.verify(|tt: &TokenTree| !matches!(tt, TokenTree::Group(g) if g.delimiter() == Delimiter::Brace)),
+    )
+    .map(|tokens: Vec<TokenTree>| TokenStream::from_iter(tokens))
+    .parse_next(input)
+}
+
+fn block(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
+    let (group, taken) = brace_group.with_taken().parse_next(input)?;
+
+    parse_group(&taken[0], &group, |input| {
+        terminated(template, eof).parse_next(input)
+    })
+}
+
+// Parses the tokens of a group, an error at their end points to the closing delimiter.
+fn parse_group<O>(
+    tt: &TokenTree,
+    group: &Group,
+    mut parser: impl FnMut(&mut &[TokenTree]) -> PResult<O>,
+) -> PResult<O> {
+    let (_, close) = Location::delimiters(tt, group);
+    let positions = source::position(tt).map(|position| position.inner);
+    let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+
+    source::scope(&tokens, positions, || {
+        let mut tokens = tokens.as_slice();
+        parser(&mut tokens).map_err(|err| err.map(|err| err.at_end(close)))
+    })
+}
+
+fn text(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
     repeat(
         1..,
         alt((
-            html_entity,
+            html_entity
+                .with_taken()
+                .map(|(entity, taken)| Node::Text(entity, Location::of(taken))),
             any.verify(|tt: &TokenTree| match tt {
                 TokenTree::Group(_) => false,
                 TokenTree::Punct(p) if p.as_char() == '<' => false,
                 _ => true,
             })
-            .map(|tt: TokenTree| tt.to_string()),
+            .with_taken()
+            .map(|(tt, taken): (TokenTree, _)| Node::Text(tt.to_string(), Location::of(taken))),
         )),
     )
-    .fold(String::new, |mut acc, item| {
-        if !acc.is_empty() {
-            acc.push(' ');
-        }
-        acc.push_str(&item);
-        acc
-    })
     .parse_next(input)
 }
 
-fn tag(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, Vec<Node>)> {
-    let open_tag =
-        (lt, tag_name, attributes, gt).map(|(lt, tag_name, (expr_defs, attributes), gt)| {
-            let mut nodes = Vec::new();
-
-            if attributes.is_empty() {
-                nodes.push(Node::Text(format!("{lt}{tag_name}{gt}")));
-            } else {
-                nodes.push(Node::Text(format!("{lt}{tag_name}")));
-                nodes.extend(attributes);
-                nodes.push(Node::Text(format!("{gt}")));
-            }
+fn tag(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
+    let open_tag = ((lt, tag_name).with_taken(), attributes, gt.with_taken()).map(
+        |(((lt, tag_name), start), attributes, (gt, end))| {
+            let mut nodes = vec![Node::Element(Element::Open(
+                tag_name.clone(),
+                Location::of(&start[1..2]),
+            ))];
+            nodes.extend(start_tag_nodes(lt, &tag_name, start, attributes, gt, end));
 
-            (expr_defs, nodes)
-        });
+            nodes
+        },
+    );
 
-    let close_tag = (lt, slash, tag_name, gt).map(|(lt, slash, tag_name, gt)| {
-        (
-            TokenStream::new(),
-            vec![Node::Text(format!("{lt}{slash}{tag_name}{gt}"))],
-        )
-    });
+    let close_tag =
+        (lt, slash, tag_name, gt)
+            .with_taken()
+            .map(|((lt, slash, tag_name, gt), taken)| {
+                vec![
+                    Node::Element(Element::Close(tag_name.clone(), Location::of(&taken[2..3]))),
+                    Node::Text(format!("{lt}{slash}{tag_name}{gt}"), Location::of(taken)),
+                ]
+            });
+
+    let self_close_tag = (
+        (lt, tag_name).with_taken(),
+        attributes,
+        (slash, gt).with_taken(),
+    )
+        .map(
+            |(((lt, tag_name), start), attributes, ((slash, gt), end))| {
+                let mut nodes = Vec::new();
 
-    let self_close_tag = (lt, tag_name, attributes, slash, gt).map(
-        |(lt, tag_name, (expr_defs, attributes), slash, gt)| {
-            let mut nodes = Vec::new();
+                // Only void elements can be self-closing in html, `<div/>` becomes `<div></div>`.
+                let end_text = if is_void_element(&tag_name) {
+                    format!("{slash}{gt}")
+                } else {
+                    format!("{gt}{lt}{slash}{tag_name}{gt}")
+                };
+
+                if attributes.is_empty() {
+                    nodes.push(Node::Text(
+                        format!("{lt}{tag_name}{end_text}"),
+                        Location::of(start).to(Location::of(end)),
+                    ));
+                } else {
+                    nodes.push(Node::Text(format!("{lt}{tag_name}"), Location::of(start)));
+                    nodes.extend(attribute_nodes(attributes));
+                    nodes.push(Node::Text(end_text, Location::of(end)));
+                }
 
-            if attributes.is_empty() {
-                nodes.push(Node::Text(format!("{lt}{tag_name}{slash}{gt}")));
-            } else {
-                nodes.push(Node::Text(format!("{lt}{tag_name}")));
-                nodes.extend(attributes);
-                nodes.push(Node::Text(format!("{slash}{gt}")));
-            }
-            (expr_defs, nodes)
-        },
-    );
+                nodes
+            },
+        );
 
     let doctype = (
         lt,
@@ -256,11 +919,12 @@ fn tag(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, Vec<Node>)> {
         ident.verify(|i| i.to_string().to_lowercase() == "html"),
         gt,
     )
-        .map(|(lt, exclamation, ident, ident2, gt)| {
-            (
-                TokenStream::new(),
-                vec![Node::Text(format!("{lt}{exclamation}{ident} {ident2}{gt}"))],
-            )
+        .with_taken()
+        .map(|((lt, exclamation, ident, ident2, gt), taken)| {
+            vec![Node::Text(
+                format!("{lt}{exclamation}{ident} {ident2}{gt}"),
+                Location::of(taken),
+            )]
         });
 
     alt((
@@ -269,12 +933,94 @@ fn tag(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, Vec<Node>)> {
         self_close_tag,
         doctype,
         html_comment,
-        any.map(|tt: TokenTree| (TokenStream::new(), vec![Node::Text(tt.to_string())])),
+        any.with_taken().map(|(tt, taken): (TokenTree, _)| {
+            vec![Node::Text(tt.to_string(), Location::of(taken))]
+        }),
     ))
     .parse_next(input)
 }
 
-fn tag_name(input: &mut &[TokenTree]) -> ModalResult<String> {
+fn start_tag_nodes(
+    lt: TokenStream,
+    tag_name: &str,
+    start: &[TokenTree],
+    attributes: Vec<Attribute>,
+    gt: TokenStream,
+    end: &[TokenTree],
+) -> Vec<Node> {
+    if attributes.is_empty() {
+        return vec![Node::Text(
+            format!("{lt}{tag_name}{gt}"),
+            Location::of(start).to(Location::of(end)),
+        )];
+    }
+
+    let mut nodes = vec![Node::Text(format!("{lt}{tag_name}"), Location::of(start))];
+    nodes.extend(attribute_nodes(attributes));
+    nodes.push(Node::Text(format!("{gt}"), Location::of(end)));
+
+    nodes
+}
+
+// <script>"..." {expr} r#"..."#</script>, the string literals are written as they are.
+// Bodies that are not only literals and expressions are parsed like any other tag.
+fn raw_text_element(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
+    let (((open_lt, name), start), attributes, (open_gt, end)) = (
+        (
+            lt,
+            tag_name.verify(|name: &str| RAW_TEXT_ELEMENTS.contains(&name)),
+        )
+            .with_taken(),
+        attributes,
+        gt.with_taken(),
+    )
+        .parse_next(input)?;
+
+    let parts = repeat(0.., raw_text_part).parse_next(input)?;
+
+    let ((close_lt, slash, _, close_gt), close) = (
+        lt,
+        slash,
+        tag_name.verify(|close_name: &str| close_name == name),
+        gt,
+    )
+        .with_taken()
+        .parse_next(input)?;
+
+    // The body takes no whitespace from the source, it starts right after `>` and ends at `</`.
+    let body_location = Location {
+        start: Location::of(end).end,
+        ..Location::of(end)
+    };
+    let close_location = Location {
+        start: body_location.end,
+        ..Location::of(close)
+    };
+
+    let mut nodes = start_tag_nodes(open_lt, &name, start, attributes, open_gt, end);
+    nodes.push(Node::RawText(name.clone(), parts, body_location));
+    nodes.push(Node::Text(
+        format!("{close_lt}{slash}{name}{close_gt}"),
+        close_location,
+    ));
+
+    Ok(nodes)
+}
+
+fn raw_text_part(input: &mut &[TokenTree]) -> PResult<RawTextPart> {
+    alt((
+        any.verify_map(|tt: TokenTree| match tt {
+            TokenTree::Literal(lit) => parse2::<syn::LitStr>(quote! { #lit })
+                .ok()
+                .map(|lit| RawTextPart::Text(lit.value())),
+            _ => None,
+        }),
+        expr.map(RawTextPart::Expr),
+    ))
+    .parse_next(input)
+}
+
+fn tag_name(input: &mut &[TokenTree]) -> PResult<String> {
     let tag_ident_start = ident.verify_map(|i| {
         let i = i.to_string();
         let mut chars = i.chars();
@@ -324,49 +1070,116 @@ fn tag_name(input: &mut &[TokenTree]) -> ModalResult<String> {
         .parse_next(input)
 }
 
-fn attributes(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, Vec<Node>)> {
-    repeat(0.., attribute)
-        .fold(
-            || (TokenStream::new(), Vec::new()),
-            |(mut expr_defs, mut items), (expr_def, name, value)| {
-                expr_defs.extend(expr_def);
+fn attributes(input: &mut &[TokenTree]) -> PResult<Vec<Attribute>> {
+    repeat(0.., attribute).parse_next(input)
+}
 
-                items.push(Node::Text(name));
-                if let Some(val) = value {
-                    items.push(val);
-                }
-                (expr_defs, items)
-            },
-        )
-        .parse_next(input)
+// Attributes are written as they are, unless the tag spreads an `Attributes` value.
+fn attribute_nodes(attributes: Vec<Attribute>) -> Vec<Node> {
+    if attributes
+        .iter()
+        .any(|a| matches!(a, Attribute::Spread(..)))
+    {
+        let location = attributes
+            .iter()
+            .map(Attribute::location)
+            .reduce(Location::to)
+            .unwrap_or_else(|| Span::call_site().into());
+
+        return vec![Node::Attributes(attributes, location)];
+    }
+
+    attributes
+        .into_iter()
+        .flat_map(|attribute| match attribute {
+            Attribute::Text(nodes, _, _) => nodes,
+            Attribute::Expr(name, expr, location) => {
+                vec![Node::AttributeExpr(name, expr, location)]
+            }
+            Attribute::Spread(..) => unreachable!(),
+        })
+        .collect()
 }
 
-fn attribute(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, String, Option<Node>)> {
-    (
-        attribute_name,
-        opt((
-            equal,
-            alt((
-                expr.map(|(expr_def, expr)| (expr_def, Node::Expr(expr))),
-                attribute_value.map(|attr_val| (TokenStream::new(), Node::Text(attr_val))),
-            )),
-        )),
+impl Attribute {
+    fn location(&self) -> Location {
+        match self {
+            Attribute::Text(nodes, _, _) => nodes
+                .iter()
+                .map(Node::location)
+                .reduce(Location::to)
+                .unwrap_or_else(|| Span::call_site().into()),
+            Attribute::Expr(_, _, location) | Attribute::Spread(_, location) => *location,
+        }
+    }
+}
+
+fn attribute(input: &mut &[TokenTree]) -> PResult<Attribute> {
+    let expr_attribute = (attribute_name.with_taken(), equal, expr.with_taken()).map(
+        |((attribute_name, name_taken), _, (expr, expr_taken))| {
+            let location = Location::of(name_taken).to(Location::of(expr_taken));
+            Attribute::Expr(attribute_name, expr, location)
+        },
+    );
+
+    let text_attribute = (
+        attribute_name.with_taken(),
+        opt((equal.with_taken(), attribute_value.with_taken())),
     )
-        .map(|(attribute_name, equal_value)| {
-            let mut expr_defs = TokenStream::new();
+        .map(|((attribute_name, name_taken), equal_value)| {
+            let mut nodes = vec![Node::Text(attribute_name.clone(), Location::of(name_taken))];
+            let mut value = None;
+
+            if let Some(((equal, equal_taken), (attr_val, value_taken))) = equal_value {
+                nodes.push(Node::Text(equal.to_string(), Location::of(equal_taken)));
+                value = Some(attr_val.clone());
+                nodes.push(Node::Text(attr_val, Location::of(value_taken)));
+            }
 
-            if let Some((equal, (expr_def, value))) = equal_value {
-                expr_defs.extend(expr_def);
+            Attribute::Text(nodes, attribute_name, value)
+        });
 
-                (expr_defs, format!("{attribute_name}{equal}"), Some(value))
-            } else {
-                (expr_defs, attribute_name.to_string(), None)
+    alt((spread_attribute, expr_attribute, text_attribute)).parse_next(input)
+}
+
+// {..attrs}
+fn spread_attribute(input: &mut &[TokenTree]) -> PResult<Attribute> {
+    any.verify_map(|tt: TokenTree| match &tt {
+        TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => {
+            let tokens: Vec<TokenTree> = g.stream().into_iter().collect();
+            match tokens.as_slice() {
+                [TokenTree::Punct(a), TokenTree::Punct(b), rest @ ..]
+                    if a.as_char() == '.' && b.as_char() == '.' && !rest.is_empty() =>
+                {
+                    let expr: TokenStream = rest.iter().cloned().collect();
+                    Some(quote! { (#expr) })
+                }
+                _ => None,
             }
-        })
-        .parse_next(input)
+        }
+        _ => None,
+    })
+    .with_taken()
+    .map(|(expr, taken)| Attribute::Spread(expr, Location::of(taken)))
+    .parse_next(input)
 }
 
-fn attribute_name(input: &mut &[TokenTree]) -> ModalResult<String> {
+// The text of a literal attribute value, with the escapes of a string literal resolved.
+fn literal_value(value: &str) -> String {
+    syn::parse_str::<syn::LitStr>(value)
+        .map(|lit| lit.value())
+        .unwrap_or_else(|_| unquote(value).to_owned())
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+fn attribute_name(input: &mut &[TokenTree]) -> PResult<String> {
     repeat(
         1..,
         any.verify(|tt: &TokenTree| match tt {
@@ -386,7 +1199,7 @@ fn attribute_name(input: &mut &[TokenTree]) -> ModalResult<String> {
     .parse_next(input)
 }
 
-fn attribute_value(input: &mut &[TokenTree]) -> ModalResult<String> {
+fn attribute_value(input: &mut &[TokenTree]) -> PResult<String> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Literal(lit) => {
             let s = lit.to_string();
@@ -405,7 +1218,7 @@ fn attribute_value(input: &mut &[TokenTree]) -> ModalResult<String> {
     .parse_next(input)
 }
 
-fn html_entity(input: &mut &[TokenTree]) -> ModalResult<String> {
+fn html_entity(input: &mut &[TokenTree]) -> PResult<String> {
     let amp = any.verify(|tt: &TokenTree| matches!(tt, TokenTree::Punct(p) if p.as_char() == '&'));
 
     let body = alt((
@@ -464,56 +1277,54 @@ fn html_entity(input: &mut &[TokenTree]) -> ModalResult<String> {
         .parse_next(input)
 }
 
-fn html_comment(input: &mut &[TokenTree]) -> ModalResult<(TokenStream, Vec<Node>)> {
-    let comment_start =
-        (lt, exclamation, hyphen, hyphen).map(|(lt, exclamation, hyphen, hyphen2)| {
-            Node::Text(format!("{lt}{exclamation}{hyphen}{hyphen2}"))
-        });
+fn html_comment(input: &mut &[TokenTree]) -> PResult<Vec<Node>> {
+    let comment_start = (lt, exclamation, hyphen, hyphen).with_taken().map(
+        |((lt, exclamation, hyphen, hyphen2), taken)| {
+            Node::Text(
+                format!("{lt}{exclamation}{hyphen}{hyphen2}"),
+                Location::of(taken),
+            )
+        },
+    );
 
     let comment_end = (hyphen, hyphen, gt)
-        .map(|(hyphen, hyphen2, gt)| Node::Text(format!("{hyphen}{hyphen2}{gt}")));
+        .with_taken()
+        .map(|((hyphen, hyphen2, gt), taken)| {
+            Node::Text(format!("{hyphen}{hyphen2}{gt}"), Location::of(taken))
+        });
 
     let comment_body_with_end = repeat_till(
         0..,
         alt((
-            expr.map(|(expr_def, expr)| (expr_def, vec![Node::Expr(expr)])),
+            expr.with_taken()
+                .map(|(expr, taken)| Node::Expr(expr, Location::of(taken))),
             any.verify(
                 |tt| !matches!(tt, TokenTree::Group(g) if g.delimiter() == Delimiter::Brace),
             )
-            .map(|tt: TokenTree| (TokenStream::new(), vec![Node::Text(tt.to_string())])),
+            .with_taken()
+            .map(|(tt, taken): (TokenTree, _)| Node::Text(tt.to_string(), Location::of(taken))),
         )),
         comment_end,
     )
-    .map(
-        |(items, comment_end): (Vec<(TokenStream, Vec<Node>)>, Node)| {
-            let mut all_nodes = Vec::new();
-            let mut all_expr_defs = TokenStream::new();
-
-            for (expr_defs, nodes) in items {
-                all_expr_defs.extend(expr_defs);
-                all_nodes.extend(nodes);
-            }
-
-            all_nodes.push(comment_end);
-
-            (all_expr_defs, all_nodes)
-        },
-    );
+    .map(|(mut nodes, comment_end): (Vec<Node>, Node)| {
+        nodes.push(comment_end);
+        nodes
+    });
 
     (comment_start, comment_body_with_end)
-        .map(|(comment_start, (expr_defs, nodes))| {
+        .map(|(comment_start, nodes)| {
             let mut all_nodes = Vec::with_capacity(nodes.len() + 1);
             all_nodes.push(comment_start);
             all_nodes.extend(nodes);
 
-            (expr_defs, all_nodes)
+            all_nodes
         })
         .parse_next(input)
 }
 
 // TOKENS
 
-fn ident(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
+fn ident(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Ident(i) => Some(quote! {#i}),
         _ => None,
@@ -524,7 +1335,7 @@ fn ident(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
     .parse_next(input)
 }
 
-fn lt(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
+fn lt(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Punct(p) if p.as_char() == '<' => Some(quote! {#p}),
         _ => None,
@@ -533,7 +1344,7 @@ fn lt(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
     .parse_next(input)
 }
 
-fn gt(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
+fn gt(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Punct(p) if p.as_char() == '>' => Some(quote! {#p}),
         _ => None,
@@ -542,7 +1353,7 @@ fn gt(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
     .parse_next(input)
 }
 
-fn equal(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
+fn equal(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Punct(p) if p.as_char() == '=' => Some(quote! {#p}),
         _ => None,
@@ -551,7 +1362,16 @@ fn equal(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
     .parse_next(input)
 }
 
-fn hyphen(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
+fn colon(input: &mut &[TokenTree]) -> PResult<TokenStream> {
+    any.verify_map(|tt: TokenTree| match tt {
+        TokenTree::Punct(p) if p.as_char() == ':' => Some(quote! {#p}),
+        _ => None,
+    })
+    .context(StrContext::Expected(StrContextValue::CharLiteral(':')))
+    .parse_next(input)
+}
+
+fn hyphen(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Punct(p) if p.as_char() == '-' => Some(quote! {#p}),
         _ => None,
@@ -560,7 +1380,7 @@ fn hyphen(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
     .parse_next(input)
 }
 
-fn slash(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
+fn slash(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Punct(p) if p.as_char() == '/' => Some(quote! {#p}),
         _ => None,
@@ -569,7 +1389,7 @@ fn slash(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
     .parse_next(input)
 }
 
-fn exclamation(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
+fn exclamation(input: &mut &[TokenTree]) -> PResult<TokenStream> {
     any.verify_map(|tt: TokenTree| match tt {
         TokenTree::Punct(p) if p.as_char() == '!' => Some(quote! {#p}),
         _ => None,
@@ -577,3 +1397,40 @@ fn exclamation(input: &mut &[TokenTree]) -> ModalResult<TokenStream> {
     .context(StrContext::Expected(StrContextValue::CharLiteral('!')))
     .parse_next(input)
 }
+
+fn at(input: &mut &[TokenTree]) -> PResult<TokenStream> {
+    any.verify_map(|tt: TokenTree| match tt {
+        TokenTree::Punct(p) if p.as_char() == '@' => Some(quote! {#p}),
+        _ => None,
+    })
+    .context(StrContext::Expected(StrContextValue::CharLiteral('@')))
+    .parse_next(input)
+}
+
+fn comma(input: &mut &[TokenTree]) -> PResult<TokenStream> {
+    any.verify_map(|tt: TokenTree| match tt {
+        TokenTree::Punct(p) if p.as_char() == ',' => Some(quote! {#p}),
+        _ => None,
+    })
+    .context(StrContext::Expected(StrContextValue::CharLiteral(',')))
+    .parse_next(input)
+}
+
+fn keyword<'a>(keyword: &'static str) -> impl Parser<&'a [TokenTree], Ident, ErrMode<ParseError>> {
+    any.verify_map(move |tt: TokenTree| match tt {
+        TokenTree::Ident(i) if i == keyword => Some(i),
+        _ => None,
+    })
+    .context(StrContext::Expected(StrContextValue::StringLiteral(
+        keyword,
+    )))
+}
+
+fn brace_group(input: &mut &[TokenTree]) -> PResult<Group> {
+    any.verify_map(|tt: TokenTree| match tt {
+        TokenTree::Group(g) if g.delimiter() == Delimiter::Brace => Some(g),
+        _ => None,
+    })
+    .context(StrContext::Expected(StrContextValue::CharLiteral('{')))
+    .parse_next(input)
+}
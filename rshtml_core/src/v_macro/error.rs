@@ -0,0 +1,173 @@
+use super::Location;
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote_spanned;
+use std::cmp::Ordering;
+use winnow::error::{AddContext, ParserError, StrContext};
+use winnow::stream::Stream;
+
+/// An error of the `v!` parser, kept at the token where parsing failed.
+#[derive(Debug)]
+pub struct ParseError {
+    remaining: usize, // tokens left from the failing one, fewer means the parser got further
+    details: Box<Details>,
+}
+
+#[derive(Debug, Default)]
+struct Details {
+    found: Option<(Location, String)>, // None at the end of the input
+    expected: Vec<String>,
+    labels: Vec<&'static str>,
+    notes: Vec<(Location, String)>,
+}
+
+impl ParseError {
+    /// Replaces what the failing parsers expected, e.g. with the name of a closing tag.
+    pub fn expecting(mut self, expected: String) -> Self {
+        self.details.expected = vec![expected];
+        self
+    }
+
+    pub fn with_note(mut self, location: Location, note: String) -> Self {
+        self.details.notes.push((location, note));
+        self
+    }
+
+    /// Errors at the end of a group's tokens are reported at its closing delimiter.
+    pub fn at_end(mut self, location: Location) -> Self {
+        if self.details.found.is_none() {
+            self.details.found = Some((location, "end of input".to_owned()));
+        }
+        self
+    }
+
+    pub fn into_diagnostic(self) -> Diagnostic {
+        let details = *self.details;
+        let (location, found) = details
+            .found
+            .unwrap_or_else(|| (Span::call_site().into(), "end of input".to_owned()));
+
+        let mut message = match details.expected.as_slice() {
+            [] => format!("unexpected {found}"),
+            [expected] => format!("expected {expected}, found {found}"),
+            expected => format!("expected one of {}, found {found}", expected.join(", ")),
+        };
+
+        for label in &details.labels {
+            message = format!("{label}: {message}");
+        }
+
+        Diagnostic {
+            location,
+            message,
+            notes: details.notes,
+        }
+    }
+}
+
+impl ParserError<&[TokenTree]> for ParseError {
+    type Inner = Self;
+
+    fn from_input(input: &&[TokenTree]) -> Self {
+        Self {
+            remaining: input.len(),
+            details: Box::new(Details {
+                found: input.first().map(|tt| (Location::token(tt), describe(tt))),
+                ..Details::default()
+            }),
+        }
+    }
+
+    // Alternatives failing at the same token are merged into an "expected one of" list.
+    fn or(mut self, other: Self) -> Self {
+        match self.remaining.cmp(&other.remaining) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => {
+                let details = &mut self.details;
+                for expected in other.details.expected {
+                    if !details.expected.contains(&expected) {
+                        details.expected.push(expected);
+                    }
+                }
+                if details.labels.is_empty() {
+                    details.labels = other.details.labels;
+                }
+                self
+            }
+        }
+    }
+
+    fn into_inner(self) -> Result<Self::Inner, Self> {
+        Ok(self)
+    }
+}
+
+impl AddContext<&[TokenTree], StrContext> for ParseError {
+    fn add_context(
+        mut self,
+        _input: &&[TokenTree],
+        _token_start: &<&[TokenTree] as Stream>::Checkpoint,
+        context: StrContext,
+    ) -> Self {
+        let details = &mut self.details;
+        match context {
+            StrContext::Label(label) => details.labels.push(label),
+            StrContext::Expected(expected) => {
+                let expected = expected.to_string();
+                if !details.expected.contains(&expected) {
+                    details.expected.push(expected);
+                }
+            }
+            _ => (),
+        }
+        self
+    }
+}
+
+fn describe(tt: &TokenTree) -> String {
+    match tt {
+        TokenTree::Group(_) => "a group".to_owned(),
+        tt => format!("`{tt}`"),
+    }
+}
+
+/// A `v!` error with its location and notes pointing at related tokens.
+pub struct Diagnostic {
+    pub location: Location,
+    pub message: String,
+    pub notes: Vec<(Location, String)>,
+}
+
+impl Diagnostic {
+    pub fn new(location: impl Into<Location>, message: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+            message: message.into(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_note(mut self, location: impl Into<Location>, note: impl Into<String>) -> Self {
+        self.notes.push((location.into(), note.into()));
+        self
+    }
+
+    /// A `compile_error!` at the failing token, and one for every note.
+    pub fn to_compile_error(&self) -> TokenStream {
+        let mut ts = compile_error(
+            self.location.span,
+            &format!("compile error: {}", self.message),
+        );
+
+        for (location, note) in &self.notes {
+            ts.extend(compile_error(location.span, &format!("note: {note}")));
+        }
+
+        ts
+    }
+}
+
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let message = syn::LitStr::new(message, span);
+    quote_spanned! { span => compile_error!(#message); }
+}
@@ -0,0 +1,267 @@
+//! Where the tokens of a `v_file!` source are in the file.
+//!
+//! Tokens lexed by the compiler from a string all share the call site span, so their line
+//! and column are found again in the file text. A token is looked up by its place in the
+//! token slice the parser reads, which is registered with `scope` while it is parsed.
+
+use super::Location;
+use proc_macro2::{Delimiter, LineColumn, Span, TokenTree};
+use std::{cell::RefCell, mem, rc::Rc};
+
+#[derive(Clone)]
+pub(super) struct Position {
+    pub open: Location,  // the whole token, or the opening delimiter of a group
+    pub close: Location, // the closing delimiter of a group
+    pub inner: Rc<[Position]>,
+}
+
+// The token slices being parsed with their positions, innermost last.
+thread_local! {
+    static SCOPES: RefCell<Vec<Scope>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Scope {
+    start: usize,
+    len: usize,
+    positions: Rc<[Position]>,
+}
+
+/// Registers the positions of `tokens` while `parse` reads them.
+pub(super) fn scope<R>(
+    tokens: &[TokenTree],
+    positions: Option<Rc<[Position]>>,
+    parse: impl FnOnce() -> R,
+) -> R {
+    struct Guard(bool);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            if self.0 {
+                SCOPES.with(|scopes| scopes.borrow_mut().pop());
+            }
+        }
+    }
+
+    let _guard = Guard(match positions {
+        Some(positions) if positions.len() == tokens.len() => {
+            SCOPES.with(|scopes| {
+                scopes.borrow_mut().push(Scope {
+                    start: tokens.as_ptr() as usize,
+                    len: tokens.len(),
+                    positions,
+                })
+            });
+            true
+        }
+        _ => false,
+    });
+
+    parse()
+}
+
+/// The position of a token of a registered slice.
+pub(super) fn position(tt: &TokenTree) -> Option<Position> {
+    let address = tt as *const TokenTree as usize;
+
+    SCOPES.with(|scopes| {
+        scopes.borrow().iter().rev().find_map(|scope| {
+            let offset = address.checked_sub(scope.start)?;
+            let index = offset / mem::size_of::<TokenTree>();
+            (index < scope.len).then(|| scope.positions[index].clone())
+        })
+    })
+}
+
+/// Finds the tokens lexed from `source` in its text, `None` if they do not follow it.
+pub(super) fn locate(source: &str, tokens: &[TokenTree]) -> Option<Rc<[Position]>> {
+    let mut cursor = Cursor {
+        rest: source,
+        line: 1,
+        column: 0,
+    };
+
+    cursor.stream(tokens)
+}
+
+struct Cursor<'a> {
+    rest: &'a str,
+    line: usize,
+    column: usize, // in chars, like `LineColumn`
+}
+
+impl Cursor<'_> {
+    fn stream(&mut self, tokens: &[TokenTree]) -> Option<Rc<[Position]>> {
+        let mut positions = Vec::with_capacity(tokens.len());
+
+        while positions.len() < tokens.len() {
+            self.skip_trivia();
+
+            // A doc comment is lexed as an attribute, `#`, `!` for an inner one and `[doc = ".."]`.
+            if let Some(location) = self.doc_comment(tokens[positions.len()].span()) {
+                let attribute = match tokens[positions.len()..] {
+                    [_, TokenTree::Punct(ref bang), ..] if bang.as_char() == '!' => 3,
+                    _ => 2,
+                };
+                for tt in tokens.get(positions.len()..positions.len() + attribute)? {
+                    positions.push(uniform(tt, location));
+                }
+                continue;
+            }
+
+            let position = self.token(&tokens[positions.len()])?;
+            positions.push(position);
+        }
+
+        Some(positions.into())
+    }
+
+    fn token(&mut self, tt: &TokenTree) -> Option<Position> {
+        match tt {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => return None,
+                };
+
+                let open = self.eat(open, group.span_open())?;
+                let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+                let inner = self.stream(&tokens)?;
+                self.skip_trivia();
+                let close = self.eat(close, group.span_close())?;
+
+                Some(Position { open, close, inner })
+            }
+            tt => {
+                let location = self.eat(&tt.to_string(), tt.span())?;
+                Some(uniform(tt, location))
+            }
+        }
+    }
+
+    fn here(&self) -> LineColumn {
+        LineColumn {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn advance(&mut self, len: usize) {
+        let (taken, rest) = self.rest.split_at(len);
+        for c in taken.chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.rest = rest;
+    }
+
+    fn eat(&mut self, text: &str, span: Span) -> Option<Location> {
+        if !self.rest.starts_with(text) {
+            return None;
+        }
+
+        let start = self.here();
+        self.advance(text.len());
+
+        Some(Location {
+            span,
+            start,
+            end: self.here(),
+        })
+    }
+
+    // Whitespace and comments that are not doc comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            let trimmed = self.rest.trim_start();
+            self.advance(self.rest.len() - trimmed.len());
+
+            if is_doc_comment(self.rest) {
+                return;
+            } else if self.rest.starts_with("//") {
+                let len = self.rest.find('\n').unwrap_or(self.rest.len());
+                self.advance(len);
+            } else if self.rest.starts_with("/*") {
+                let len = block_comment_len(self.rest);
+                self.advance(len);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn doc_comment(&mut self, span: Span) -> Option<Location> {
+        if !is_doc_comment(self.rest) {
+            return None;
+        }
+
+        let len = if self.rest.starts_with("//") {
+            self.rest.find('\n').unwrap_or(self.rest.len())
+        } else {
+            block_comment_len(self.rest)
+        };
+        let text = &self.rest[..len];
+
+        self.eat(text, span)
+    }
+}
+
+// `///` and `//!`, `/**` and `/*!`, but not `////` or `/**/`.
+fn is_doc_comment(text: &str) -> bool {
+    (text.starts_with("///") && !text.starts_with("////"))
+        || text.starts_with("//!")
+        || (text.starts_with("/**") && !text.starts_with("/***") && !text.starts_with("/**/"))
+        || text.starts_with("/*!")
+}
+
+// Block comments nest, an unclosed one runs to the end of the text.
+fn block_comment_len(text: &str) -> usize {
+    let mut depth = 0;
+    let mut i = 0;
+    let bytes = text.as_bytes();
+
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'/', Some(b'*')) => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', Some(b'/')) => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    text.len()
+}
+
+// A token and all tokens in it at the same location, e.g. those of a doc comment.
+fn uniform(tt: &TokenTree, location: Location) -> Position {
+    let inner = match tt {
+        TokenTree::Group(group) => group
+            .stream()
+            .into_iter()
+            .map(|tt| uniform(&tt, location))
+            .collect(),
+        _ => Rc::from([]),
+    };
+
+    Position {
+        open: Location {
+            span: tt.span(),
+            ..location
+        },
+        close: location,
+        inner,
+    }
+}
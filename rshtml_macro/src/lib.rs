@@ -1,7 +1,7 @@
 #![doc(hidden)]
 
 use proc_macro::TokenStream;
-use rshtml_core::{process_template, v_macro};
+use rshtml_core::{component, process_template, v_macro};
 use syn::{Data, DeriveInput, Fields, LitStr, parse_macro_input};
 
 #[proc_macro_derive(RsHtml, attributes(rshtml))]
@@ -12,30 +12,36 @@ pub fn rshtml_derive(input: TokenStream) -> TokenStream {
     let struct_generics = &input.generics;
     let struct_fields = get_struct_fields(&input.data);
 
-    let (template_name, no_warn) = match parse_template_path_from_attrs(&input.attrs) {
-        Ok(rshtml_config) => {
-            let template_name = if let Some(path) = rshtml_config.path {
-                path
-            } else {
-                let struct_name_str = struct_name.to_string();
-                let mut template_file = if let Some(stripped) = struct_name_str.strip_suffix("Page")
-                {
-                    format!("{stripped}.rs.html")
+    let (template_name, no_warn, adaptive_size, escape) =
+        match parse_template_path_from_attrs(&input.attrs) {
+            Ok(rshtml_config) => {
+                let template_name = if let Some(path) = rshtml_config.path {
+                    path
                 } else {
-                    format!("{struct_name_str}.rs.html")
+                    let struct_name_str = struct_name.to_string();
+                    let mut template_file =
+                        if let Some(stripped) = struct_name_str.strip_suffix("Page") {
+                            format!("{stripped}.rs.html")
+                        } else {
+                            format!("{struct_name_str}.rs.html")
+                        };
+
+                    // template_file.to_lowercase()
+                    template_file = to_snake_case(&template_file);
+                    template_file
                 };
 
-                // template_file.to_lowercase()
-                template_file = to_snake_case(&template_file);
-                template_file
-            };
-
-            (template_name, rshtml_config.no_warn)
-        }
-        Err(err) => {
-            return err.to_compile_error().into();
-        }
-    };
+                (
+                    template_name,
+                    rshtml_config.no_warn,
+                    rshtml_config.adaptive_size,
+                    rshtml_config.escape,
+                )
+            }
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
 
     TokenStream::from(process_template(
         template_name,
@@ -43,18 +49,24 @@ pub fn rshtml_derive(input: TokenStream) -> TokenStream {
         struct_generics,
         struct_fields,
         no_warn,
+        adaptive_size,
+        escape,
     ))
 }
 
 struct RsHtmlConfig {
     pub path: Option<String>,
     pub no_warn: bool,
+    pub adaptive_size: bool,
+    pub escape: Option<String>,
 }
 
 fn parse_template_path_from_attrs(attrs: &[syn::Attribute]) -> syn::Result<RsHtmlConfig> {
     let mut config = RsHtmlConfig {
         path: None,
         no_warn: false,
+        adaptive_size: false,
+        escape: None,
     };
 
     for attr in attrs {
@@ -72,6 +84,18 @@ fn parse_template_path_from_attrs(attrs: &[syn::Attribute]) -> syn::Result<RsHtm
                     return Ok(());
                 }
 
+                if meta.path.is_ident("escape") {
+                    let value = meta.value()?;
+                    let s: LitStr = value.parse()?;
+                    config.escape = Some(s.value());
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("adaptive_size") {
+                    config.adaptive_size = true;
+                    return Ok(());
+                }
+
                 Err(meta.error("unsupported rshtml property"))
             })?;
         }
@@ -126,3 +150,13 @@ fn get_struct_fields(data: &Data) -> Vec<String> {
 pub fn v(input: TokenStream) -> TokenStream {
     TokenStream::from(v_macro::compile(input.into()))
 }
+
+#[proc_macro]
+pub fn v_file(input: TokenStream) -> TokenStream {
+    TokenStream::from(v_macro::compile_file(input.into()))
+}
+
+#[proc_macro_attribute]
+pub fn component(attr: TokenStream, item: TokenStream) -> TokenStream {
+    TokenStream::from(component::compile(attr.into(), item.into()))
+}
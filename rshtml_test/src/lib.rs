@@ -75,6 +75,15 @@ mod tests {
         };
 
         let s = homepage.render().unwrap();
+        assert!(s.contains("let users_let = [{"));
+
+        assert_eq!(homepage.render_to_vec().unwrap(), s.as_bytes());
+        assert_eq!(homepage.render_to_string().unwrap(), s);
+        assert_eq!(homepage.display().to_string(), s);
+
+        let mut out = Vec::new();
+        homepage.render_to_io(&mut out).unwrap();
+        assert_eq!(out, s.as_bytes());
 
         print!("{s}");
     }
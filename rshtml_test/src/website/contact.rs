@@ -1,4 +1,19 @@
-use rshtml::{traits::View, v};
+use rshtml::{
+    traits::{Component, View},
+    v,
+};
+use std::fmt;
+
+pub struct Contact<'a> {
+    pub title: &'a str,
+    pub email: &'a str,
+}
+
+impl Component for Contact<'_> {
+    fn render(&self, _children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result {
+        contact(self.title, self.email).render(out)
+    }
+}
 
 pub fn contact(title: &str, email: &str) -> impl View {
     v! {
@@ -1,5 +1,5 @@
 use crate::website::{
-    about::about, contact::contact, footer, home::home, layout::layout, navbar::navbar,
+    about::about, contact::Contact, footer, home::home, layout::layout, navbar::navbar,
     services::services,
 };
 use chrono::Utc;
@@ -22,10 +22,7 @@ pub fn index() -> impl View {
             { home("Introduction", home_time) }
             { services("Our Services") }
             { about("About our company") }
-            { contact("Send a message", email) }
-
-
-            <Contact title="Send a message"/>
+            <Contact title="Send a message" email={email}/>
         </div>
     };
 
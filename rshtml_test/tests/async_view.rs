@@ -0,0 +1,129 @@
+use rshtml::{
+    Async, AsyncView, Memo, render_async,
+    traits::{Component, View, ViewExt},
+    v,
+};
+use std::{
+    fmt::{self, Write},
+    time::Duration,
+};
+use tokio::sync::oneshot;
+
+async fn load_name(id: u32) -> String {
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    format!("user<{id}>")
+}
+
+struct Sidebar {
+    id: u32,
+}
+
+impl AsyncView for Sidebar {
+    async fn render_async(self, out: &mut (dyn fmt::Write + Send)) -> fmt::Result {
+        let name = load_name(self.id).await;
+        v!(<aside>{name}</aside>).render(out)
+    }
+}
+
+#[tokio::test]
+async fn test_async_leaves() {
+    let title = "Title";
+    let users = [1, 2];
+
+    let view = v! {
+        <h1>{title}</h1>
+        @for id in users {
+            <p>{Async::new(async move { v!(<b>{load_name(id).await}</b>) })}</p>
+        }
+        {Async::new(Sidebar { id: 3 })}
+    };
+
+    // The render future is `Send`, so it can run on any worker thread.
+    let out = tokio::spawn(async move { render_async(&view).await })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        out.unwrap(),
+        "<h1>Title</h1>\n        <p><b>user&lt;1&gt;</b></p><p><b>user&lt;2&gt;</b></p>\n        <aside>user&lt;3&gt;</aside>"
+    );
+}
+
+#[tokio::test]
+async fn test_concurrent_and_ordered() {
+    let (sender, receiver) = oneshot::channel::<&str>();
+
+    // The first leaf can only finish once the second one has run.
+    let view = v! {
+        {Async::new(async move { receiver.await.unwrap() })}|{Async::new(async move {
+            sender.send("first").unwrap();
+            "second"
+        })}
+    };
+
+    let out = tokio::time::timeout(Duration::from_secs(1), render_async(&view))
+        .await
+        .expect("async leaves are resolved concurrently")
+        .unwrap();
+
+    assert_eq!(out, "first|second");
+}
+
+#[tokio::test]
+async fn test_nested_async_leaves() {
+    let view = v! {
+        <main>{Async::new(async {
+            let name = load_name(1).await;
+            v!(<p>{name}</p>{Async::new(async { load_name(2).await })})
+        })}</main>
+    };
+
+    assert_eq!(
+        render_async(&view).await.unwrap(),
+        "<main><p>user&lt;1&gt;</p>user&lt;2&gt;</main>"
+    );
+}
+
+#[test]
+fn test_sync_render_fails() {
+    let view = v!(<p>{Async::new(async { "text" })}</p>);
+
+    let mut out = String::new();
+    assert!(view.render(&mut out).is_err());
+}
+
+// Passes the output on through its own writer, like an escaper or a component might.
+struct Boxed;
+
+struct Forward<'a>(&'a mut dyn fmt::Write);
+
+impl fmt::Write for Forward<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s)
+    }
+}
+
+impl Component for Boxed {
+    fn render(&self, children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result {
+        let out = &mut Forward(out);
+        out.write_str("[")?;
+        children.render(out)?;
+        out.write_str("]")
+    }
+}
+
+#[tokio::test]
+async fn test_wrapped_writers() {
+    let view = v!(<p>a<Boxed>b{Async::new(async { "leaf" })}c</Boxed>d</p>);
+    assert_eq!(render_async(&view).await.unwrap(), "<p>a[bleafc]d</p>");
+
+    let view = v!(<p>{Async::new(async { "leaf" }).or_else_view("none")}</p>);
+    assert_eq!(render_async(&view).await.unwrap(), "<p>leaf</p>");
+}
+
+#[tokio::test]
+async fn test_buffered_leaf_fails() {
+    let view = Memo::new(v!(<p>{Async::new(async { "leaf" })}</p>));
+
+    assert!(render_async(&view).await.is_err());
+}
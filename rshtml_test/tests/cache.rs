@@ -0,0 +1,162 @@
+use rshtml::{
+    RsHtml,
+    cache::{CacheStore, Cached, LruStore, default_store},
+    traits::RsHtml as _,
+    traits::View,
+    v,
+};
+use std::{cell::Cell, sync::Arc, thread, time::Duration};
+
+#[derive(RsHtml)]
+#[rshtml(path = "cache.rs.html")]
+struct CachedNav {
+    key: String,
+    items: Vec<&'static str>,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "cache_options.rs.html")]
+struct CachedFooter {
+    key: &'static str,
+    text: &'static str,
+    store: Arc<dyn CacheStore>,
+}
+
+fn render(view: &impl View) -> String {
+    let mut out = String::new();
+    view.render(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn test_cached_renders_once() {
+    let renders = &Cell::new(0);
+    let view = Cached::new(
+        "test_cached_renders_once",
+        v!(@lazy <p>{renders.set(renders.get() + 1); "text"}</p>),
+    )
+    .store(Arc::new(LruStore::new(8)));
+
+    assert_eq!(render(&view), "<p>text</p>");
+    assert_eq!(render(&view), "<p>text</p>");
+    assert_eq!(renders.get(), 1);
+    assert_eq!(view.text_size(), "<p>text</p>".len());
+}
+
+#[test]
+fn test_cached_ttl() {
+    let store = Arc::new(LruStore::new(8));
+    let renders = &Cell::new(0);
+    let view = Cached::new("key", v!(@lazy {renders.set(renders.get() + 1); "text"}))
+        .ttl(Duration::from_millis(20))
+        .store(store.clone());
+
+    render(&view);
+    render(&view);
+    assert_eq!(renders.get(), 1);
+
+    thread::sleep(Duration::from_millis(40));
+    render(&view);
+    assert_eq!(renders.get(), 2);
+}
+
+#[test]
+fn test_lru_eviction() {
+    let store = LruStore::new(2);
+    store.insert("a", "a".into(), None);
+    store.insert("b", "b".into(), None);
+    assert!(store.get("a").is_some());
+
+    store.insert("c", "c".into(), None);
+
+    assert_eq!(store.len(), 2);
+    assert!(store.get("b").is_none());
+    assert_eq!(store.get("a").as_deref(), Some("a"));
+    assert_eq!(store.get("c").as_deref(), Some("c"));
+
+    store.remove("a");
+    assert!(store.get("a").is_none());
+
+    store.insert("d", "d".into(), None);
+    store.insert("c", "c2".into(), None);
+    store.insert("e", "e".into(), None);
+
+    assert_eq!(store.len(), 2);
+    assert!(store.get("d").is_none());
+    assert_eq!(store.get("c").as_deref(), Some("c2"));
+    assert_eq!(store.get("e").as_deref(), Some("e"));
+}
+
+#[test]
+fn test_peek_keeps_recency() {
+    let store = LruStore::new(2);
+    store.insert("a", "a".into(), None);
+    store.insert("b", "b".into(), None);
+    assert_eq!(store.peek("a").as_deref(), Some("a"));
+
+    store.insert("c", "c".into(), None);
+    assert!(store.get("a").is_none());
+    assert!(store.get("b").is_some());
+}
+
+#[test]
+fn test_cache_block_in_template() {
+    let first = CachedNav {
+        key: "test_cache_block_in_template".to_owned(),
+        items: vec!["a", "b"],
+    };
+    let second = CachedNav {
+        key: first.key.clone(),
+        items: vec!["c"],
+    };
+    let other = CachedNav {
+        key: "test_cache_block_in_template_other".to_owned(),
+        items: vec!["c"],
+    };
+
+    assert_eq!(
+        first.render().unwrap(),
+        "<nav><ul><li>a</li><li>b</li></ul></nav>\n"
+    );
+    assert_eq!(
+        second.render().unwrap(),
+        "<nav><ul><li>a</li><li>b</li></ul></nav>\n"
+    );
+    assert_eq!(other.render().unwrap(), "<nav><ul><li>c</li></ul></nav>\n");
+}
+
+#[test]
+fn test_cache_block_options() {
+    let store = Arc::new(LruStore::new(8));
+    let footer = |text| CachedFooter {
+        key: "test_cache_block_options",
+        text,
+        store: store.clone(),
+    };
+
+    assert_eq!(footer("a").render().unwrap(), "<footer>a</footer>\n");
+    assert_eq!(footer("b").render().unwrap(), "<footer>a</footer>\n");
+    assert_eq!(
+        store
+            .get("cache_options.rs.html:test_cache_block_options")
+            .as_deref(),
+        Some("a")
+    );
+}
+
+#[test]
+fn test_cache_keys_per_template() {
+    let key = "test_cache_keys_per_template";
+    let footer = CachedFooter {
+        key,
+        text: "footer",
+        store: default_store(),
+    };
+    let nav = CachedNav {
+        key: key.to_owned(),
+        items: vec!["a"],
+    };
+
+    assert_eq!(footer.render().unwrap(), "<footer>footer</footer>\n");
+    assert_eq!(nav.render().unwrap(), "<nav><ul><li>a</li></ul></nav>\n");
+}
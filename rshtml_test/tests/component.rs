@@ -0,0 +1,132 @@
+use rshtml::{
+    component,
+    traits::{Component, View},
+    v,
+};
+use std::fmt;
+
+fn render(view: impl View) -> String {
+    let mut out = String::with_capacity(view.text_size());
+    view.render(&mut out).unwrap();
+    out
+}
+
+struct Card<'a> {
+    title: &'a str,
+    count: usize,
+    highlighted: bool,
+}
+
+impl Component for Card<'_> {
+    fn render(&self, children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result {
+        let class = if self.highlighted {
+            "card active"
+        } else {
+            "card"
+        };
+
+        v! {
+            <div class={class}><h2>{self.title} ({self.count})</h2>{children}</div>
+        }
+        .render(out)
+    }
+}
+
+mod ui {
+    use super::*;
+
+    #[component]
+    pub fn Badge(label: String) -> impl View {
+        v!(<span class="badge">{label}</span>)
+    }
+
+    #[component]
+    pub fn Panel<T: fmt::Display>(
+        title: T,
+        tags: &[&str],
+        footer: impl View + Clone,
+        children: impl View,
+    ) -> impl View {
+        let tags = tags.join(", ");
+        let title = title.to_string();
+        v!(<div title={title}>{children}<small>{tags}</small>{footer}</div>)
+    }
+}
+
+// Records the size its children report before rendering them.
+struct Sized;
+
+impl Component for Sized {
+    fn render(&self, children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result {
+        write!(out, "{}:", children.text_size())?;
+        children.render(out)
+    }
+}
+
+#[test]
+fn test_component_with_children() {
+    let title = "Users";
+    let users = ["Ann", "Bob"];
+
+    let view = v! {
+        <section>
+            <Card title={title} count={users.len()} highlighted>
+                @for user in users.iter() {
+                    <ui::Badge label={user.to_string()}/>
+                }
+            </Card>
+        </section>
+    };
+
+    assert_eq!(
+        render(view),
+        "<section>
+            <div class=\"card active\"><h2>Users (2)</h2><span class=\"badge\">Ann</span><span class=\"badge\">Bob</span></div>
+        </section>"
+    );
+}
+
+#[test]
+fn test_component_literal_props() {
+    let view = v!(<Card title="Empty" count=0 highlighted={false}/>);
+
+    assert_eq!(render(view), "<div class=\"card\"><h2>Empty (0)</h2></div>");
+}
+
+#[test]
+fn test_function_components() {
+    let tags = ["a", "b"];
+    let footer = v!(<i>end</i>);
+
+    let view = v! {
+        <ui::Panel title="Tags" tags={&tags[..]} footer={&footer}>
+            <ui::Badge label={"x".to_string()}/>
+        </ui::Panel>
+    };
+
+    assert_eq!(
+        render(view),
+        "<div title=\"Tags\"><span class=\"badge\">x</span><small>a, b</small><i>end</i></div>"
+    );
+}
+
+#[test]
+fn test_children_text_size() {
+    let name = "Ann";
+    let view = v!(<Sized><p>{name}</p></Sized>);
+
+    assert_eq!(render(view), "10:<p>Ann</p>");
+}
+
+#[test]
+fn test_owned_props() {
+    let label = String::from("x");
+    let view = v!(<div><ui::Badge label={label}/></div>);
+
+    assert_eq!(render(&view), "<div><span class=\"badge\">x</span></div>");
+    assert_eq!(render(&view), "<div><span class=\"badge\">x</span></div>");
+
+    let label = String::from("y");
+    let lazy = v!(@lazy <div><ui::Badge label={label}/></div>);
+    assert_eq!(render(&lazy), "<div><span class=\"badge\">y</span></div>");
+}
@@ -0,0 +1,171 @@
+use rshtml::{Attributes, Context, Escaper, RsHtml, traits::RsHtml as _, traits::View, v};
+
+#[derive(RsHtml)]
+#[rshtml(path = "context.rs.html")]
+struct ContextPage {
+    url: &'static str,
+    text: &'static str,
+    color: &'static str,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "unquoted.rs.html")]
+struct UnquotedPage {
+    url: &'static str,
+    text: &'static str,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "branches.rs.html")]
+struct BranchPage {
+    first: bool,
+    url: &'static str,
+}
+
+fn render(view: &impl View) -> String {
+    let mut out = String::new();
+    view.render(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn test_context_of_attribute() {
+    assert_eq!(Context::attribute("href", true), Context::Url);
+    assert_eq!(Context::attribute(" SRC", true), Context::Url);
+    assert_eq!(Context::attribute("href", false), Context::Attribute);
+    assert_eq!(Context::attribute("onclick", true), Context::Js);
+    assert_eq!(Context::attribute("style", true), Context::Css);
+    assert_eq!(Context::attribute("title", true), Context::Attribute);
+    assert_eq!(Context::attribute_value("href"), Context::Url);
+    assert_eq!(Context::attribute_value("onclick"), Context::Attribute);
+    assert_eq!(Context::attribute_value("style"), Context::Attribute);
+}
+
+#[test]
+fn test_urls() {
+    let url = |url: &str| {
+        let mut out = String::new();
+        Context::Url
+            .write(&mut out, |out| out.write_str(url))
+            .unwrap();
+        out
+    };
+
+    assert_eq!(
+        url("https://example.com/?a=1&b=2"),
+        "https://example.com/?a=1&amp;b=2"
+    );
+    assert_eq!(url("/users/1"), "/users/1");
+    assert_eq!(url("page#top:1"), "page#top:1");
+    assert_eq!(url("mailto:a@b.c"), "mailto:a@b.c");
+    assert_eq!(url("javascript:alert(1)"), "about:invalid");
+    assert_eq!(url(" JavaScript:alert(1)"), "about:invalid");
+    assert_eq!(url("java\tscript:alert(1)"), "about:invalid");
+    assert_eq!(url("data:text/html,x"), "about:invalid");
+}
+
+#[test]
+fn test_template_contexts() {
+    let page = ContextPage {
+        url: "javascript:alert(1)",
+        text: "a'\"</script>",
+        color: "red;}",
+    };
+
+    assert_eq!(
+        page.render().unwrap(),
+        concat!(
+            r#"<a href="about:invalid" title="a&#39;&quot;&lt;/script&gt;" "#,
+            r#"onclick="greet('a\u0027\u0022\u003C\u002Fscript\u003E')" "#,
+            r#"style="color: red\3B \7D ">"#,
+            "a&#39;&quot;&lt;&#x2F;script&gt;</a>\n",
+            r#"<img src="/img/javascript:alert(1)">"#,
+            "\n",
+            r#"<script>const name = "a\u0027\u0022\u003C\u002Fscript\u003E";</script>"#,
+            "\n",
+            r#"<style>.theme-red\3B \7D  { color: red; }</style>"#,
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn test_unquoted_attribute_values() {
+    let page = UnquotedPage {
+        url: "javascript:alert(1)",
+        text: "x onmouseover=alert(1)`",
+    };
+
+    assert_eq!(
+        page.render().unwrap(),
+        concat!(
+            "<a href=about:invalid title=x&#32;onmouseover&#61;alert(1)&#96; ",
+            "class=item-x&#32;onmouseover&#61;alert(1)&#96;>x onmouseover=alert(1)`</a>\n",
+        )
+    );
+}
+
+#[test]
+fn test_contexts_in_branches() {
+    for first in [true, false] {
+        let page = BranchPage {
+            first,
+            url: "javascript:alert(1)",
+        };
+
+        assert_eq!(
+            page.render().unwrap(),
+            "<a href=\"about:invalid \">a</a>\n<a href=\"about:invalid \">b</a>\n"
+        );
+    }
+}
+
+#[test]
+fn test_v_attribute_contexts() {
+    let url = "javascript:alert(1)";
+    let handler = "x');alert('1";
+    let style = "red;}";
+    let view = v!(<a href={url} onclick={handler} style={style}>link</a>);
+
+    assert_eq!(
+        render(&view),
+        r#"<a href="about:invalid" onclick="x&#39;);alert(&#39;1" style="red;}">link</a>"#
+    );
+
+    let attributes = Attributes::new().with("src", url).with("alt", "a\"b");
+    assert_eq!(
+        render(&v!(<img {..attributes}>)),
+        r#"<img src="about:invalid" alt="a&quot;b">"#
+    );
+
+    let url = Some("https://example.com");
+    assert_eq!(
+        render(&v!(<a href={url}>link</a>)),
+        r#"<a href="https://example.com">link</a>"#
+    );
+}
+
+#[test]
+fn test_v_whole_attribute_values() {
+    let style = "color: red; margin: 0";
+    let handler = "go('x')";
+    let view = v!(<p style={style} onclick={handler} title={"a: b; c"}>text</p>);
+    assert_eq!(
+        render(&view),
+        r#"<p style="color: red; margin: 0" onclick="go(&#39;x&#39;)" title="a: b; c">text</p>"#
+    );
+
+    let attributes = Attributes::new()
+        .with("style", style)
+        .with("onclick", handler);
+    assert_eq!(
+        render(&v!(<p {..attributes}>text</p>)),
+        r#"<p style="color: red; margin: 0" onclick="go(&#39;x&#39;)">text</p>"#
+    );
+
+    let url = "/search?q=a b&page=2";
+    assert_eq!(
+        render(&v!(<a href={url}>link</a>)),
+        r#"<a href="/search?q=a b&amp;page=2">link</a>"#
+    );
+}
@@ -0,0 +1,189 @@
+use rshtml::{
+    Escaper, PreEscaped, RsHtml,
+    escaper::{Html, Json, Text, Xml},
+    traits::RsHtml as _,
+    traits::View,
+    v,
+};
+use std::fmt;
+
+#[derive(RsHtml)]
+#[rshtml(path = "feed.rs.xml")]
+struct Feed {
+    title: &'static str,
+    items: Vec<&'static str>,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "mail.rs.txt")]
+struct Mail {
+    name: &'static str,
+    order: &'static str,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "report.rs.html", escape = "json")]
+struct Report {
+    name: &'static str,
+    note: &'static str,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "mail.rs.txt", escape = "Shout")]
+struct ShoutingMail {
+    name: &'static str,
+    order: &'static str,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "entries.rs.xml")]
+struct Entries {
+    title: &'static str,
+}
+
+struct Shout;
+
+impl Escaper for Shout {
+    fn escape_str(&self, input: &str, out: &mut dyn fmt::Write) -> fmt::Result {
+        out.write_str(&input.to_uppercase())
+    }
+}
+
+fn escape(escaper: impl Escaper, input: &str) -> String {
+    let mut out = String::new();
+    escaper.write(&mut out, |out| out.write_str(input)).unwrap();
+    out
+}
+
+#[test]
+fn test_builtin_escapers() {
+    let input = "<a href='/x'>\"Tom\" & \\Jerry\n</a>";
+
+    assert_eq!(
+        escape(Html, input),
+        "&lt;a href=&#39;&#x2F;x&#39;&gt;&quot;Tom&quot; &amp; \\Jerry\n&lt;&#x2F;a&gt;"
+    );
+    assert_eq!(
+        escape(Xml, input),
+        "&lt;a href=&apos;/x&apos;&gt;&quot;Tom&quot; &amp; \\Jerry\n&lt;/a&gt;"
+    );
+    assert_eq!(escape(Text, input), input);
+    assert_eq!(
+        escape(Json, input),
+        "<a href='/x'>\\\"Tom\\\" & \\\\Jerry\\n</a>"
+    );
+    assert_eq!(escape(Json, "\u{1}\t"), "\\u0001\\t");
+}
+
+#[test]
+fn test_escaper_from_extension() {
+    let feed = Feed {
+        title: "Tom & Jerry's",
+        items: vec!["<b>", "a&b"],
+    };
+
+    assert_eq!(
+        feed.render().unwrap(),
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<rss version=\"2.0\"><channel><title>Tom &amp; Jerry&apos;s</title>\n",
+            "<item><title>&lt;b&gt;</title><link>https://example.com/?q=&lt;b&gt;</link></item>",
+            "<item><title>a&amp;b</title><link>https://example.com/?q=a&amp;b</link></item>",
+            "</channel></rss>\n",
+        )
+    );
+
+    let mail = Mail {
+        name: "O'Neil",
+        order: "#1 & #2",
+    };
+
+    assert_eq!(
+        mail.render().unwrap(),
+        "Hello O'Neil,\n\nYour order <#1 & #2> is on its way & will arrive soon.\n"
+    );
+}
+
+#[test]
+fn test_escaper_from_attribute() {
+    let report = Report {
+        name: "\"quoted\"",
+        note: "line\nbreak",
+    };
+
+    assert_eq!(
+        report.render().unwrap(),
+        "{\"name\": \"\\\"quoted\\\"\", \"note\": \"line\\nbreak\"}\n"
+    );
+
+    let mail = ShoutingMail {
+        name: "tom",
+        order: "a1",
+    };
+
+    assert_eq!(
+        mail.render().unwrap(),
+        "Hello TOM,\n\nYour order <A1> is on its way & will arrive soon.\n"
+    );
+}
+
+#[test]
+fn test_escaper_in_v() {
+    let title = "Tom & Jerry's";
+    let inner = v!(<b>{title}</b>);
+
+    let xml = v!(escape = xml, <title>{title}</title>{inner});
+    assert_eq!(
+        xml.render_to_string().unwrap(),
+        "<title>Tom &amp; Jerry&apos;s</title><b>Tom &amp; Jerry&#39;s</b>"
+    );
+
+    let text = v!(escape = text, @lazy <p>{title} {PreEscaped("<i>")}</p>);
+    assert_eq!(text.render_to_string().unwrap(), "<p>Tom & Jerry's <i></p>");
+
+    let shout = v!(escape = Shout, <p>{title}</p>);
+    assert_eq!(shout.render_to_string().unwrap(), "<p>TOM & JERRY'S</p>");
+
+    let html = v!(escape = html, <p>{title}</p>);
+    assert_eq!(
+        html.render_to_string().unwrap(),
+        "<p>Tom &amp; Jerry&#39;s</p>"
+    );
+}
+
+#[test]
+fn test_escaper_in_v_containers() {
+    let name = Some("a & b");
+    let tags = vec!["<x>", "y's"];
+    let note = std::borrow::Cow::Borrowed("c & d");
+    let pair = ("e & f", 1);
+    let inner = v!(<b>{"&"}</b>);
+
+    let text = v!(escape = text, {name} {&tags} {note} {pair} {Some(&inner)});
+    assert_eq!(
+        text.render_to_string().unwrap(),
+        "a & b <x>y's c & d e & f1 <b>&amp;</b>"
+    );
+
+    let xml = v!(escape = xml, <item title={name} hidden={false} href={"?a=1&b='2'"}>{tags}</item>);
+    assert_eq!(
+        xml.render_to_string().unwrap(),
+        "<item title=\"a &amp; b\" href=\"?a=1&amp;b=&apos;2&apos;\">&lt;x&gt;y&apos;s</item>"
+    );
+}
+
+#[test]
+fn test_components_keep_their_escaper() {
+    let entries = Entries {
+        title: "Tom & 'Jerry'",
+    };
+
+    assert_eq!(
+        entries.render().unwrap(),
+        concat!(
+            "<feed>Tom &amp; &apos;Jerry&apos;",
+            "<entry title=\"Tom &amp; &#39;Jerry&#39;\">Tom &amp; &#39;Jerry&#39;</entry>",
+            "Tom & 'Jerry'</feed>\n",
+        )
+    );
+}
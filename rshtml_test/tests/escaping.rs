@@ -0,0 +1,142 @@
+use rshtml::{Context, Escaper, EscapingWriter, ViewFn, traits::AttributeValue};
+use std::fmt::{self, Write};
+
+// The char by char escaping the writers used before scanning for runs, kept as the reference.
+fn reference(input: &str, escapes: &[(char, &str)]) -> String {
+    let mut out = String::new();
+    for c in input.chars() {
+        match escapes.iter().find(|(e, _)| *e == c) {
+            Some((_, escaped)) => out.push_str(escaped),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+const HTML: [(char, &str); 6] = [
+    ('&', "&amp;"),
+    ('<', "&lt;"),
+    ('>', "&gt;"),
+    ('"', "&quot;"),
+    ('\'', "&#39;"),
+    ('/', "&#x2F;"),
+];
+
+const ATTRIBUTE: [(char, &str); 5] = [
+    ('&', "&amp;"),
+    ('<', "&lt;"),
+    ('>', "&gt;"),
+    ('"', "&quot;"),
+    ('\'', "&#39;"),
+];
+
+// A xorshift generator, the inputs are the same on every run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+const ALPHABET: [char; 20] = [
+    'a', 'Z', '0', ' ', '\n', '&', '<', '>', '"', '\'', '/', '#', ';', 'é', 'ß', '€', '中', '😀',
+    '\u{0}', '\u{7f}',
+];
+
+fn inputs() -> Vec<String> {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut inputs: Vec<String> = [
+        "",
+        "plain text without any escaped character at all",
+        "&<>\"'/",
+        "<script>alert('x')</script>",
+        "a&b",
+        "&&&&&&&&&&&&&&&&&",
+        "12345678&",
+        "1234567&8",
+        "€€€€€€€€<",
+        "😀😀<😀😀",
+    ]
+    .map(str::to_owned)
+    .to_vec();
+
+    for _ in 0..2000 {
+        let len = rng.below(64);
+        let dense = rng.below(4) == 0;
+        let input = (0..len)
+            .map(|_| {
+                if dense {
+                    ALPHABET[rng.below(ALPHABET.len())]
+                } else {
+                    ALPHABET[rng.below(4)]
+                }
+            })
+            .collect();
+        inputs.push(input);
+    }
+
+    inputs
+}
+
+// Writes the input in pieces split at char boundaries, as `write!` does with its arguments.
+fn write_split(out: &mut dyn Write, input: &str, rng: &mut Rng) -> fmt::Result {
+    let mut rest = input;
+    while !rest.is_empty() {
+        let mut at = rng.below(rest.len() + 1);
+        while !rest.is_char_boundary(at) {
+            at += 1;
+        }
+        let (piece, tail) = rest.split_at(at);
+        out.write_str(piece)?;
+        rest = tail;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_escaping_writer_matches_reference() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    for input in inputs() {
+        let mut out = String::new();
+        EscapingWriter { inner: &mut out }
+            .write_str(&input)
+            .unwrap();
+        assert_eq!(out, reference(&input, &HTML), "input: {input:?}");
+
+        let mut out = String::new();
+        write_split(&mut EscapingWriter { inner: &mut out }, &input, &mut rng).unwrap();
+        assert_eq!(out, reference(&input, &HTML), "split input: {input:?}");
+    }
+}
+
+#[test]
+fn test_attribute_escaping_matches_reference() {
+    for input in inputs() {
+        let mut out = String::new();
+        Context::Attribute
+            .write(&mut out, |out| out.write_str(&input))
+            .unwrap();
+        assert_eq!(out, reference(&input, &ATTRIBUTE), "input: {input:?}");
+    }
+}
+
+#[test]
+fn test_quote_escaping_matches_reference() {
+    for input in inputs() {
+        let view = ViewFn::new((|out: &mut dyn Write| out.write_str(&input), 0));
+        let mut out = String::new();
+        view.render_attribute("title", &mut out).unwrap();
+
+        let expected = format!("title=\"{}\"", reference(&input, &[('"', "&quot;")]));
+        assert_eq!(out, expected, "input: {input:?}");
+    }
+}
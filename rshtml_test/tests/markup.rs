@@ -0,0 +1,76 @@
+use rshtml::{
+    Markup, PreEscaped, RsHtml, SafeHtml,
+    functions::{json, json_script},
+    traits::RsHtml as _,
+    traits::View,
+    v,
+};
+use std::fmt;
+
+struct Sanitized(&'static str);
+
+impl SafeHtml for Sanitized {
+    fn write_html(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        out.write_str(&self.0.replace("<script>", ""))
+    }
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "markup.rs.html")]
+struct MarkupPage {
+    html: Markup,
+    text: &'static str,
+    sanitized: Sanitized,
+    users: Vec<&'static str>,
+}
+
+fn render(view: &impl View) -> String {
+    let mut out = String::new();
+    view.render(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn test_markup_in_v() {
+    let html = PreEscaped("<b>bold</b>");
+    let text = "<b>bold</b>";
+    let view = v!(<p title={html}>{html} {text}</p><script>{html}</script>);
+
+    assert_eq!(
+        render(&view),
+        "<p title=\"&lt;b&gt;bold&lt;/b&gt;\"><b>bold</b> &lt;b&gt;bold&lt;&#x2F;b&gt;</p><script><b>bold</b></script>"
+    );
+    assert_eq!(html.text_size(), text.len());
+}
+
+#[test]
+fn test_markup_in_template() {
+    let page = MarkupPage {
+        html: PreEscaped("<i>x</i>".to_owned()),
+        text: "<i>x</i>",
+        sanitized: Sanitized("<script><u>y</u>"),
+        users: vec!["</script><script>alert(1)", "\" onmouseover=\"alert(1)"],
+    };
+
+    assert_eq!(
+        page.render().unwrap(),
+        concat!(
+            "<div title=\"&lt;i&gt;x&lt;/i&gt;\"><i>x</i> &lt;i&gt;x&lt;&#x2F;i&gt; <u>y</u></div>\n",
+            "<script>const users = [\"\\u003c/script\\u003e\\u003cscript\\u003ealert(1)\",",
+            "\"\\\" onmouseover=\\\"alert(1)\"];</script>\n",
+            "<div data-users=\"[&quot;\\u003c/script\\u003e\\u003cscript\\u003ealert(1)&quot;,",
+            "&quot;\\&quot; onmouseover=\\&quot;alert(1)&quot;]\" ",
+            "data-json=\"[&quot;&lt;/script&gt;&lt;script&gt;alert(1)&quot;,",
+            "&quot;\\&quot; onmouseover=\\&quot;alert(1)&quot;]\"></div>\n",
+        )
+    );
+}
+
+#[test]
+fn test_markup_composes() {
+    let inner: Markup = v!(<b>{"<"}</b>).render_to_string().map(PreEscaped).unwrap();
+    let outer = v!(<p>{&inner}</p>);
+
+    assert_eq!(render(&outer), "<p><b>&lt;</b></p>");
+    assert_eq!(inner.to_string(), "<b>&lt;</b>");
+}
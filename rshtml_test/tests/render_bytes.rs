@@ -0,0 +1,77 @@
+use bytes::BytesMut;
+use rshtml::{Error, traits::View, v};
+use std::io;
+
+struct FailingWriter;
+
+impl io::Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_render_to_vec() {
+    let name = "<b>";
+    let view = v!(<p>Hello {name}</p>);
+
+    assert_eq!(view.render_to_vec().unwrap(), b"<p>Hello &lt;b&gt;</p>");
+}
+
+#[test]
+fn test_render_to_io() {
+    let name = "<b>";
+    let view = v!(<p>Hello {name}</p>);
+
+    let mut out = io::Cursor::new(Vec::new());
+    view.render_to_io(&mut out).unwrap();
+    assert_eq!(out.into_inner(), b"<p>Hello &lt;b&gt;</p>");
+
+    let err = view.render_to_io(&mut FailingWriter).unwrap_err();
+    assert!(matches!(&err, Error::Io(err) if err.kind() == io::ErrorKind::BrokenPipe));
+    assert_eq!(err.to_string(), "failed to write the rendered view: closed");
+}
+
+#[test]
+fn test_render_to_io_is_buffered() {
+    #[derive(Default)]
+    struct CountingWriter {
+        out: Vec<u8>,
+        writes: usize,
+    }
+
+    impl io::Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            self.out.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let items = [1, 2, 3];
+    let view = v!(<ul>@for item in items {<li>{item}</li>}</ul>);
+
+    let mut out = CountingWriter::default();
+    view.render_to_io(&mut out).unwrap();
+    assert_eq!(out.out, b"<ul><li>1</li><li>2</li><li>3</li></ul>");
+    assert_eq!(out.writes, 1);
+}
+
+#[test]
+fn test_render_to_bytes() {
+    let name = "<b>";
+    let view = v!(<p>Hello {name}</p>);
+
+    let mut out = BytesMut::from("<main>");
+    view.render_to_bytes(&mut out).unwrap();
+
+    assert_eq!(&out[..], b"<main><p>Hello &lt;b&gt;</p>");
+}
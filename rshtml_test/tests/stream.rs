@@ -0,0 +1,168 @@
+use rshtml::{RsHtml, flush, traits::RsHtml as _, traits::View, v};
+use std::{
+    fmt, io,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+#[derive(RsHtml)]
+#[rshtml(path = "stream.rs.html")]
+struct StreamPage {
+    title: String,
+    items: Vec<String>,
+}
+
+fn collect(view: &impl View, chunk_size: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    view.render_chunked(chunk_size, &mut |chunk| {
+        chunks.push(chunk.to_owned());
+        Ok(())
+    })
+    .unwrap();
+    chunks
+}
+
+#[test]
+fn test_chunk_size() {
+    let text = "abcdefghij";
+    let view = v!(<p>{text}</p><p>{text}</p>);
+
+    let chunks = collect(&view, 8);
+
+    assert_eq!(chunks.concat(), "<p>abcdefghij</p><p>abcdefghij</p>");
+    assert!(chunks.len() > 1);
+    assert!(
+        chunks[..chunks.len() - 1]
+            .iter()
+            .all(|chunk| chunk.len() >= 8)
+    );
+}
+
+#[test]
+fn test_flush_in_v() {
+    let title = "Title";
+    let view = v! {
+        <head><title>{title}</title></head>{flush()}<body>body</body>
+    };
+
+    assert_eq!(
+        collect(&view, 1024),
+        ["<head><title>Title</title></head>", "<body>body</body>"]
+    );
+
+    let mut out = String::new();
+    view.render(&mut out).unwrap();
+    assert_eq!(out, "<head><title>Title</title></head><body>body</body>");
+}
+
+#[test]
+fn test_flush_in_template() {
+    let page = StreamPage {
+        title: "Title".to_owned(),
+        items: vec!["a".to_owned(), "b".to_owned()],
+    };
+
+    let mut chunks = Vec::new();
+    page.render_chunked(1024, &mut |chunk| {
+        chunks.push(chunk.to_owned());
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0], "<head><title>Title</title></head>\n");
+    assert_eq!(chunks.concat(), page.render().unwrap());
+}
+
+#[test]
+fn test_sink_error() {
+    let view = v!(<p>text</p>);
+
+    let err = view
+        .render_chunked(4, &mut |_| Err(io::Error::from(io::ErrorKind::BrokenPipe)))
+        .unwrap_err();
+
+    assert!(matches!(err, rshtml::Error::Io(err) if err.kind() == io::ErrorKind::BrokenPipe));
+}
+
+#[test]
+fn test_into_chunks() {
+    let items = ["a", "b", "c"];
+    let view =
+        v!(<ul>{items.iter().map(|item| v!(<li>{*item}</li>{flush()})).collect::<Vec<_>>()}</ul>);
+
+    let chunks = view
+        .into_chunks(1024)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        chunks,
+        ["<ul><li>a</li>", "<li>b</li>", "<li>c</li>", "</ul>"]
+    );
+}
+
+struct Panics;
+
+impl View for Panics {
+    fn render(&self, _out: &mut dyn fmt::Write) -> fmt::Result {
+        panic!("view panicked");
+    }
+}
+
+// Writes one byte at a time, counting the writes.
+struct Counted(Arc<AtomicUsize>);
+
+impl View for Counted {
+    fn render(&self, out: &mut dyn fmt::Write) -> fmt::Result {
+        for _ in 0..100 {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            out.write_str("x")?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_into_chunks_panic() {
+    let view = v!(<p>a</p>{flush()}{Panics});
+
+    let mut chunks = view.into_chunks(1024);
+    assert_eq!(chunks.next().unwrap().unwrap(), "<p>a</p>");
+    assert!(matches!(chunks.next(), Some(Err(rshtml::Error::Panic))));
+    assert!(chunks.next().is_none());
+}
+
+#[test]
+fn test_into_chunks_backpressure() {
+    let writes = Arc::new(AtomicUsize::new(0));
+    let mut chunks = Counted(writes.clone()).into_chunks(1);
+
+    assert_eq!(chunks.next().unwrap().unwrap(), "x");
+    thread::sleep(Duration::from_millis(50));
+    assert!(writes.load(Ordering::SeqCst) < 10);
+
+    assert_eq!(chunks.map(Result::unwrap).count(), 99);
+}
+
+#[test]
+fn test_flush_outside_chunked_render() {
+    assert_eq!(flush().render_to_string().unwrap(), "");
+
+    let view = v!(<p>a</p>);
+    assert_eq!(collect(&view, 1024), ["<p>a</p>"]);
+}
+
+#[tokio::test]
+async fn test_into_body() {
+    let view = v!(<head></head>{flush()}<body></body>);
+
+    let body = view.into_body(1024);
+    let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+
+    assert_eq!(&bytes[..], b"<head></head><body></body>");
+}
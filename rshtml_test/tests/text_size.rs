@@ -0,0 +1,79 @@
+use rshtml::{
+    RsHtml,
+    traits::{IntoViewIter, RsHtml as _, View},
+    v,
+};
+
+#[derive(RsHtml)]
+#[rshtml(path = "stream.rs.html", adaptive_size)]
+struct AdaptivePage {
+    title: String,
+    items: Vec<String>,
+}
+
+#[derive(RsHtml)]
+#[rshtml(path = "stream.rs.html", adaptive_size)]
+struct GenericPage<T: std::fmt::Display> {
+    title: T,
+    items: Vec<T>,
+}
+
+#[test]
+fn test_builtin_text_sizes() {
+    assert_eq!(0u8.text_size(), 1);
+    assert_eq!(1234usize.text_size(), 4);
+    assert_eq!((-1234i32).text_size(), 5);
+    assert_eq!(i64::MIN.text_size(), i64::MIN.to_string().len());
+    assert_eq!(u128::MAX.text_size(), u128::MAX.to_string().len());
+    assert_eq!('ş'.text_size(), 2);
+    assert_eq!(false.text_size(), 5);
+    assert_eq!(format_args!("static").text_size(), 6);
+
+    assert_eq!(vec!["ab", "cde"].text_size(), 5);
+    assert_eq!(["ab", "cde"][..].text_size(), 5);
+    assert_eq!(Box::new("abc").text_size(), 3);
+
+    let items = (0..4).map(|i| v!(<li>{i}</li>)).view_iter();
+    assert!(items.text_size() > 0);
+}
+
+#[test]
+fn test_v_text_size_counts_expressions() {
+    let name = String::from("a long enough user name");
+    let count = 12345;
+    let view = v!(<p>{name} {count}</p>);
+
+    let mut out = String::new();
+    view.render(&mut out).unwrap();
+
+    assert_eq!(view.text_size(), out.len());
+}
+
+#[test]
+fn test_adaptive_size() {
+    let page = AdaptivePage {
+        title: "Title".to_owned(),
+        items: (0..200).map(|i| format!("item {i}")).collect(),
+    };
+
+    let first = page.render().unwrap();
+    let second = page.render().unwrap();
+
+    assert_eq!(first, second);
+    assert!(second.capacity() >= first.len());
+}
+
+#[test]
+fn test_adaptive_size_generic() {
+    let long = GenericPage {
+        title: "Title".to_owned(),
+        items: (0..200).map(|i| format!("item {i}")).collect(),
+    };
+    let short = GenericPage {
+        title: 1,
+        items: vec![2, 3],
+    };
+
+    assert_eq!(long.render().unwrap(), long.render().unwrap());
+    assert!(short.render().unwrap().contains("<p>2</p><p>3</p>"));
+}
@@ -0,0 +1,17 @@
+use rshtml::{traits::View, v_file};
+
+#[test]
+fn v_file() {
+    let title = "Cards";
+    let cards = ["first", "<second>"];
+
+    let view = v_file!("cards/list.html");
+
+    let mut out = String::with_capacity(view.text_size());
+    view.render(&mut out).unwrap();
+
+    assert_eq!(
+        out,
+        "<section class=\"cards\">\n    <h2>Cards</h2>\n    <p>2 cards: first, &lt;second&gt;</p>\n</section>"
+    );
+}
@@ -1,6 +1,6 @@
 use rshtml::traits::*;
-use rshtml::{ViewFn, v};
-use std::fmt;
+use rshtml::{Attributes, ViewFn, v};
+use std::{cell::Cell, fmt};
 
 fn render_view(view: ViewFn<impl Fn(&mut dyn fmt::Write) -> fmt::Result>) -> String {
     let mut buffer = String::with_capacity(view.text_size());
@@ -11,7 +11,7 @@ fn render_view(view: ViewFn<impl Fn(&mut dyn fmt::Write) -> fmt::Result>) -> Str
 #[test]
 fn test_simple_element() {
     let view = v! { <div>Hello</div> };
-    assert_eq!(render_view(view), "<div> Hello </div>");
+    assert_eq!(render_view(view), "<div>Hello</div>");
 }
 
 #[test]
@@ -25,15 +25,14 @@ fn test_nested_elements() {
 
     let output = render_view(view);
     assert!(output.contains("<ul>"));
-    assert!(output.contains("<li> Item 1 </li>"));
+    assert!(output.contains("<li>Item 1</li>"));
 }
 
 #[test]
 fn test_attributes() {
     let view = v! { <div class="container" id="main">Content</div> };
     let output = render_view(view);
-    assert!(output.contains(r#"class= "container""#));
-    assert!(output.contains(r#"id= "main""#));
+    assert_eq!(output, r#"<div class="container" id="main">Content</div>"#);
 }
 
 #[test]
@@ -41,13 +40,13 @@ fn test_expression() {
     let name = "World";
     let count = 42;
     let view = v! { <p>Hello {name}, count is {count}</p> };
-    assert_eq!(render_view(view), "<p> Hello World , count is 42 </p>");
+    assert_eq!(render_view(view), "<p>Hello World, count is 42</p>");
 }
 
 #[test]
 fn test_self_closing() {
     let view = v! { <br /><input type="text" /><hr/> };
-    assert_eq!(render_view(view), "<br/> <input type= \"text\" /> <hr/>");
+    assert_eq!(render_view(view), "<br/><input type=\"text\" /><hr/>");
 }
 
 #[test]
@@ -59,6 +58,21 @@ fn test_html_entities() {
     assert!(output.contains("&#123;"));
 }
 
+#[test]
+fn test_whitespace() {
+    let view = v! {
+        <pre>first line,
+            indented (line)
+
+        last line.</pre>
+    };
+
+    assert_eq!(
+        render_view(view),
+        "<pre>first line,\n            indented (line)\n\n        last line.</pre>"
+    );
+}
+
 #[test]
 fn test_control_flow() {
     let is_admin = true;
@@ -70,13 +84,231 @@ fn test_control_flow() {
     assert!(render_view(view).contains("Admin"));
 }
 
+#[test]
+fn test_if_else() {
+    let role = |level: u8| {
+        v! {
+            <p>@if level > 1 {
+                <b>Admin</b>
+            } else if level == 1 {
+                Editor {level}
+            } else {
+                Guest
+            }</p>
+        }
+    };
+
+    assert_eq!(render_view(role(2)), "<p><b>Admin</b></p>");
+    assert_eq!(render_view(role(1)), "<p>Editor 1</p>");
+    assert_eq!(render_view(role(0)), "<p>Guest</p>");
+}
+
+#[test]
+fn test_for() {
+    let items = ["a", "<b>"];
+    let view = v! {
+        <ul>@for (i, item) in items.iter().enumerate() {<li id={i}>{item}</li>}</ul>
+    };
+
+    assert_eq!(
+        render_view(view),
+        "<ul><li id=\"0\">a</li><li id=\"1\">&lt;b&gt;</li></ul>"
+    );
+}
+
+#[test]
+fn test_match() {
+    let status = |value: Option<u32>| {
+        v! {
+            @match value {
+                Some(0) => { <span>zero</span> }
+                Some(n) if n > 10 => { <span>many: {n}</span> },
+                Some(n) => { <span>{n}</span> },
+                None => { <span>none</span> }
+            }
+        }
+    };
+
+    assert_eq!(render_view(status(Some(0))), "<span>zero</span>");
+    assert_eq!(render_view(status(Some(42))), "<span>many: 42</span>");
+    assert_eq!(render_view(status(Some(7))), "<span>7</span>");
+    assert_eq!(render_view(status(None)), "<span>none</span>");
+}
+
 #[test]
 fn test_dynamic_attribute() {
     let active = "\"active\"";
     let view = v! { <button class={active}>Click</button> };
     let output = render_view(view);
-    println!("{output}");
-    assert!(output.contains(r#"class= &quot;active&quot;"#));
+    assert_eq!(
+        output,
+        r#"<button class="&quot;active&quot;">Click</button>"#
+    );
+}
+
+#[test]
+fn test_attribute_escaping() {
+    let title = "Tom & \"Jerry\" <3 it's/";
+    let classes = v!(btn {"a&b"});
+    let view = v! { <a title={title} class = {classes} href={"/home"}>x</a> };
+
+    assert_eq!(
+        render_view(view),
+        r#"<a title="Tom &amp; &quot;Jerry&quot; &lt;3 it&#39;s/" class="btn a&amp;b" href="/home">x</a>"#
+    );
+}
+
+#[test]
+fn test_boolean_and_optional_attributes() {
+    let title: Option<String> = None;
+    let view = v! { <input disabled={false} checked={true} title={title} value={Some(3)}/> };
+    assert_eq!(render_view(view), r#"<input checked value="3"/>"#);
+
+    let title = Some("a & b");
+    let view = v! { <button disabled={1 > 2} title={title}>Save</button> };
+    assert_eq!(
+        render_view(view),
+        r#"<button title="a &amp; b">Save</button>"#
+    );
+}
+
+#[test]
+fn test_view_attribute_values() {
+    let classes = vec!["a", "\"b\""];
+    let label: Box<dyn View> = Box::new(v!(<i>"x"</i>));
+    let view = v! { <p class={classes} title={label} hidden={false}>text</p> };
+
+    assert_eq!(
+        render_view(view),
+        r#"<p class="a&quot;b&quot;" title="<i>&quot;x&quot;</i>">text</p>"#
+    );
+}
+
+#[test]
+fn test_attribute_spread() {
+    let attrs = Attributes::new()
+        .with("class", "primary")
+        .with("type", "submit")
+        .with("disabled", true)
+        .with("title", None::<&str>);
+    let view = v! { <button class="btn" type="button" {..&attrs} data-id={7}>Save</button> };
+    assert_eq!(
+        render_view(view),
+        r#"<button class="btn primary" type="submit" disabled data-id="7">Save</button>"#
+    );
+
+    let button = |attrs: Attributes| v! { <button {..attrs} class={"x & y"}/> };
+    let attrs: Attributes = [("class", "a"), ("id", "b")].into_iter().collect();
+    assert_eq!(
+        render_view(button(attrs)),
+        r#"<button class="a x &amp; y" id="b"></button>"#
+    );
+}
+
+#[test]
+fn test_attribute_spread_values() {
+    let attrs = Attributes::new().with("alt", "x");
+    let view = v! { <img title="a\"b" {..&attrs} class="c"/> };
+    assert_eq!(
+        render_view(view),
+        r#"<img title="a&quot;b" alt="x" class="c"/>"#
+    );
+
+    struct Failing;
+
+    impl fmt::Display for Failing {
+        fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let attrs = Attributes::new().with("title", rshtml::Attr(Failing));
+    assert!(!attrs.contains("title"));
+    assert!(v!(<p {..&attrs}>x</p>).render_to_string().is_err());
+}
+
+#[test]
+fn test_attribute_spread_names() {
+    let attrs = Attributes::new()
+        .with("data-user_id", 1)
+        .with("@click", "go()")
+        .with(":class", "x");
+    assert_eq!(
+        v!(<p {..&attrs}>x</p>).render_to_string().unwrap(),
+        r#"<p data-user_id="1" @click="go()" :class="x">x</p>"#
+    );
+
+    for name in [
+        "",
+        "a b",
+        "x\"",
+        "x>",
+        "onload=alert(1)",
+        "a/b",
+        "'x",
+        "a\nb",
+    ] {
+        let attrs = Attributes::new().with("id", "a").with(name, true);
+        assert!(!attrs.contains(name));
+        assert!(v!(<p {..&attrs}>x</p>).render_to_string().is_err());
+    }
+}
+
+#[test]
+fn test_self_closing_non_void() {
+    let view = v! { <div class="icon"/><br/><input type="text"/> };
+    assert_eq!(
+        render_view(view),
+        r#"<div class="icon"></div><br/><input type="text"/>"#
+    );
+}
+
+#[test]
+fn test_lazy() {
+    let counter = &Cell::new(1);
+    let calls = Cell::new(0);
+    let expensive = || {
+        calls.set(calls.get() + 1);
+        "x"
+    };
+
+    let render = |view: &dyn View| {
+        let mut out = String::new();
+        view.render(&mut out).unwrap();
+        out
+    };
+
+    let view = v!(@lazy <p>{counter.get()} {expensive()}</p>);
+    assert_eq!(calls.get(), 0);
+    assert_eq!(view.text_size(), "<p> </p>".len());
+
+    assert_eq!(render(&view), "<p>1 x</p>");
+    counter.set(2);
+    assert_eq!(render(&view), "<p>2 x</p>");
+    assert_eq!(calls.get(), 2);
+
+    // Only `@lazy` is the marker, a leading `lazy` is text.
+    assert_eq!(render_view(v!(lazy loading is on)), "lazy loading is on");
+    let lazy = "lazy";
+    assert_eq!(render_view(v!({lazy} dog)), "lazy dog");
+}
+
+#[test]
+fn test_raw_text_elements() {
+    let name = "</script><b>'x'";
+    let theme = "dark;}";
+
+    let view = v! {
+        <script type="module">r#"const re = /a+'b'/g; // "comment""# "\nconst t = `x ${1}`;" "const name = '" {name} "';"</script>
+        <style>".theme-" {theme} " { color: red; }"</style>
+    };
+
+    assert_eq!(
+        render_view(view),
+        r#"<script type="module">const re = /a+'b'/g; // "comment"
+const t = `x ${1}`;const name = '\u003C\u002Fscript\u003E\u003Cb\u003E\u0027x\u0027';</script>
+        <style>.theme-dark\3B \7D  { color: red; }</style>"#
+    );
 }
 
 #[test]
@@ -152,3 +384,34 @@ fn cards(title: &str, cards: &[Card]) -> impl View {
         </div>
     }
 }
+
+#[test]
+fn test_view_iter_rendered_twice() {
+    let items = ["a", "b"];
+    let view = items.iter().map(|item| v!(<li>{*item}</li>)).view_iter();
+
+    let mut out = String::new();
+    view.render(&mut out).unwrap();
+    assert_eq!(out, "<li>a</li><li>b</li>");
+
+    assert!(view.render(&mut String::new()).is_err());
+}
+
+#[test]
+fn test_view_iter_cloned() {
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+    let items = ["a".to_owned(), "b".to_owned()];
+    let list = items
+        .iter()
+        .map(|item| v!(<li>{item}</li>))
+        .view_iter_cloned();
+    assert_send_sync(&list);
+
+    let view = v!(<ul>{&list}</ul><ol>{&list}</ol>);
+
+    assert_eq!(
+        render_view(view),
+        "<ul><li>a</li><li>b</li></ul><ol><li>a</li><li>b</li></ol>"
+    );
+}
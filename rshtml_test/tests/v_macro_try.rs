@@ -1,4 +1,8 @@
-use rshtml::{traits::View, v};
+use rshtml::{
+    traits::{Component, View},
+    v,
+};
+use std::fmt;
 
 #[test]
 fn v_macro_try() {
@@ -42,13 +46,13 @@ fn v_macro_try() {
         <div>fsdf sd</div>
         {3+5}
 
-        {users.iter().map(|_user| v!(aa <User/>)).collect::<Vec<_>>()}
-        {users.iter().map(|_user| v!(bb <User/>)).collect::<Vec<_>>()}
+        {users.iter().map(|_user| v!(aa <User age={1}/>)).collect::<Vec<_>>()}
+        {users.iter().map(|_user| v!(bb <User age={2}/>)).collect::<Vec<_>>()}
         {
             if x == 5 {
                  v!(< Card/>).boxed()
             } else {
-                 v!(<SideBar/>).boxed()
+                 v!(<SideBar title="side bar"/>).boxed()
             }
          }
 
@@ -61,6 +65,34 @@ fn v_macro_try() {
     other();
 }
 
+struct User {
+    age: i32,
+}
+
+impl Component for User {
+    fn render(&self, _children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result {
+        v!(<p>age: {self.age}</p>).render(out)
+    }
+}
+
+struct Card;
+
+impl Component for Card {
+    fn render(&self, children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result {
+        v!(<div class="card">{children}</div>).render(out)
+    }
+}
+
+struct SideBar<'a> {
+    title: &'a str,
+}
+
+impl Component for SideBar<'_> {
+    fn render(&self, _children: &dyn View, out: &mut dyn fmt::Write) -> fmt::Result {
+        v!(<aside>{self.title}</aside>).render(out)
+    }
+}
+
 fn card() -> impl View {
     let x = 5;
     let s = String::from("oooo");
@@ -0,0 +1,90 @@
+use rshtml::{
+    Attributes,
+    traits::{View, ViewExt, ViewIterExt},
+    v,
+};
+use std::cell::Cell;
+
+fn render(view: impl View) -> String {
+    let mut out = String::new();
+    view.render(&mut out).unwrap();
+    out
+}
+
+// Without escaped characters the estimate is exact.
+fn assert_text_size(view: impl View) {
+    let text_size = view.text_size();
+    assert_eq!(text_size, render(view).len());
+}
+
+#[test]
+fn test_when_or() {
+    assert_eq!(render("shown".when(true)), "shown");
+    assert_eq!(render("hidden".when(false)), "");
+    assert_eq!(
+        render("items".when(false).or_else_view("No items")),
+        "No items"
+    );
+    assert_eq!(render("items".when(true).or_else_view("No items")), "items");
+    assert_eq!(render("".or_else_view("empty")), "empty");
+    assert_eq!(render(None::<&str>.or_else_view("none")), "none");
+
+    assert_text_size("items".when(false).or_else_view("No items"));
+    assert_text_size("items".when(true).or_else_view("No items"));
+}
+
+#[test]
+fn test_wrap_in() {
+    let attributes = Attributes::new().with("class", "a & b");
+
+    assert_eq!(
+        render("<content>".wrap_in("section", attributes)),
+        r#"<section class="a &amp; b">&lt;content&gt;</section>"#
+    );
+    assert_eq!(
+        render(v!(<p>text</p>).wrap_in("div", ())),
+        "<div><p>text</p></div>"
+    );
+}
+
+#[test]
+fn test_prefix_suffix() {
+    assert_eq!(render("body".prefix("<").suffix(">")), "&lt;body&gt;");
+    assert_eq!(
+        render(v!(<main></main>).prefix(v!(<header></header>))),
+        "<header></header><main></main>"
+    );
+
+    assert_text_size("body".prefix("[").suffix("]"));
+}
+
+#[test]
+fn test_join() {
+    let tags = ["rust", "html", "<js>"];
+
+    let out = render(tags.iter().map(|tag| v!(<b>{*tag}</b>)).join_views(", "));
+    assert_eq!(out, "<b>rust</b>, <b>html</b>, <b>&lt;js&gt;</b>");
+
+    assert_eq!(render(tags.iter().take(0).join_views(", ")), "");
+
+    let view = tags.iter().join_views(", ");
+    assert!(view.text_size() >= ", , ".len());
+    render(&view);
+    assert!(view.render(&mut String::new()).is_err());
+}
+
+#[test]
+fn test_memo() {
+    let renders = &Cell::new(0);
+    let view = v!(@lazy <p>{ renders.set(renders.get() + 1); renders.get() }</p>).memo();
+
+    let mut first = String::new();
+    view.render(&mut first).unwrap();
+    let mut second = String::new();
+    view.render(&mut second).unwrap();
+
+    assert_eq!(first, "<p>1</p>");
+    assert_eq!(second, "<p>1</p>");
+    assert_eq!(renders.get(), 1);
+    assert_eq!(view.text_size(), first.len());
+}
@@ -24,10 +24,7 @@ fn view_trait() {
 
     home.render(&mut out).unwrap();
 
-    assert_eq!(
-        out,
-        "<div> Home Page , title : home title , count : 7 </div>"
-    )
+    assert_eq!(out, "<div>Home Page, title:home title, count:7</div>")
 }
 
 #[test]
@@ -55,3 +52,90 @@ fn view_trait_with_v() {
     let mut out = String::with_capacity((res.text_size() as f32 * 1.1) as usize);
     res(&mut out).unwrap();
 }
+
+#[test]
+fn view_impls_for_std_types() {
+    use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+    let banner: Option<&str> = Some("<b>sale</b>");
+    let missing: Option<&str> = None;
+    let loaded: Result<&str, String> = Ok("ok");
+    let failed: Result<&str, String> = Err("failed".to_owned());
+    let header = v!(<header>head</header>);
+    let body = v!(<main>body</main>);
+    let items = ["a", "b", "c"];
+    let shared = Rc::new("rc");
+    let synced = Arc::new("arc");
+    let cow: Cow<'_, [&str]> = Cow::Borrowed(&["x", "y"]);
+
+    let res = v! {
+        {banner}{missing}|{loaded}|{failed}|{(header, body)}|{items}|{shared}{synced}|{cow}
+    };
+
+    let mut out = String::new();
+    res.render(&mut out).unwrap();
+
+    assert_eq!(
+        out,
+        "&lt;b&gt;sale&lt;&#x2F;b&gt;|ok|failed|<header>head</header><main>body</main>|abc|rcarc|xy"
+    );
+
+    assert_eq!(Some("abc").text_size(), 3);
+    assert_eq!(None::<&str>.text_size(), 0);
+    assert_eq!(Err::<&str, _>("error").text_size(), 5);
+    assert_eq!(("ab", ("c", "de")).text_size(), 5);
+    assert_eq!(["ab", "cd"].text_size(), 4);
+    assert_eq!(Arc::new("abc").text_size(), 3);
+    assert_eq!(Cow::Borrowed("abc").text_size(), 3);
+}
+
+#[test]
+fn shared_views() {
+    let title = String::from("<Title>");
+    let header: rshtml::SharedView = v!(<h1>{title}</h1>).arc();
+
+    let pages = (0..2)
+        .map(|i| {
+            let header = header.clone();
+            std::thread::spawn(move || {
+                let body = v!(<p>{i}</p>).boxed_send();
+                let page = v!({&header}{&body});
+
+                let mut out = String::new();
+                page.render(&mut out).unwrap();
+                out
+            })
+        })
+        .map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        pages,
+        [
+            "<h1>&lt;Title&gt;</h1><p>0</p>",
+            "<h1>&lt;Title&gt;</h1><p>1</p>"
+        ]
+    );
+    assert_eq!(header.text_size(), "<h1></h1>".len() + "<Title>".len());
+}
+
+#[test]
+fn render_to_string_and_display() {
+    let home = Home {
+        title: "<home>".to_owned(),
+        count: 7,
+    };
+    let view = v!(<main>{&home}</main>);
+
+    let rendered = view.render_to_string().unwrap();
+
+    assert_eq!(
+        rendered,
+        "<main><div>Home Page, title:&lt;home&gt;, count:7</div></main>"
+    );
+    assert_eq!(format!("{}", view.display()), rendered);
+    assert_eq!(home.display().to_string(), home.render_to_string().unwrap());
+
+    let children: &dyn View = &view;
+    assert_eq!((&children).display().to_string(), rendered);
+}